@@ -38,6 +38,27 @@ def sideways_contour(j):
                    for position in [(i, j) for i in range(8)]])
 
 
+def adjacent_files(j):
+    return reduce(operator.ior,
+                  [sideways_contour(neighbor)
+                   for neighbor in (j - 1, j + 1)
+                   if 0 <= neighbor < 8],
+                  0)
+
+
+def passed_span(position, forward):
+    # the locales ahead of a servant, on his file and the files beside it,
+    # that an opposing servant would have to stand on to stop him
+    rank, file = position
+    ranks_ahead = range(rank + 1, 8) if forward > 0 else range(0, rank)
+    return reduce(operator.ior,
+                  [rank_and_file_to_u64((i, j))
+                   for i in ranks_ahead
+                   for j in (file - 1, file, file + 1)
+                   if 0 <= j < 8],
+                  0)
+
+
 PONY_OPTIONS = ((+1, +2), (-1, +2), (+1, -2), (-1, -2),
                 (+2, +1), (-2, +1), (+2, -1), (-2, -1))
 
@@ -62,6 +83,14 @@ def the_book_of_life(job_description, result):
     )
 
 
+def the_lay_of_the_land(name, result):
+    return "pub static {}: [u64; {}] = [\n{}\n];\n".format(
+        name.upper().replace(' ', '_'),
+        len(result),
+        '\n'.join("    {},".format(entry) for entry in result)
+    )
+
+
 def where_the_heart_is(whose_heart, strikepoint):
     return "pub static {}: u64 = {};\n".format(
         whose_heart.upper().replace(' ', '_'),
@@ -103,8 +132,15 @@ def main():
                 ["pub static CENTER_OF_THE_WORLD: u64 = {};\n".format(
                     center_of_the_world())] +
                 [where_the_heart_is(*blargs) for blargs in all_args] +
-                ['pub static FILES: [u64; 8] = [{}];'.format(
-                  ', '.join(['%d' % sideways_contour(f) for f in range(8)]))]
+                ['pub static FILES: [u64; 8] = [{}];\n'.format(
+                  ', '.join(['%d' % sideways_contour(f) for f in range(8)]))] +
+                [the_lay_of_the_land(
+                    "adjacent files", [adjacent_files(f) for f in range(8)])] +
+                [the_lay_of_the_land(
+                    "{} passed spans".format(whose_span),
+                    [passed_span(position, forward)
+                     for position in itertools.product(range(8), repeat=2)])
+                 for whose_span, forward in (("high", 1), ("low", -1))]
             )
         )
     print("Wrote landmark.rs!")
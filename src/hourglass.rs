@@ -0,0 +1,178 @@
+use time::Duration;
+
+use karma::Score;
+use life::Patch;
+
+
+// when the clock doesn't say how many movements we need to make in the time
+// remaining, assume it's about this many
+const PRESUMED_MOVEMENTS_TO_GO: i64 = 30;
+// time lost to talking to the driver, which we can't spend thinking
+const OVERHEAD_MILLISECONDS: i64 = 50;
+// never plan to spend more than this share of what's left on one movement
+const HARD_SHARE_OF_REMAINING: f32 = 0.4;
+const HARD_MULTIPLE_OF_SOFT: i32 = 4;
+
+// a best movement that changes between iterations means we don't
+// understand the world yet ...
+const INSTABILITY_FACTOR: f32 = 1.6;
+// ... as does a score that falls by more than this
+const SCORE_DROP_MARGIN: Score = Score(30);
+const SCORE_DROP_FACTOR: f32 = 1.4;
+// and a best movement that beats the runner-up by this much (and hasn't
+// changed) needs less thought
+const DOMINANCE_MARGIN: Score = Score(300);
+const DOMINANCE_FACTOR: f32 = 0.3;
+const MAXIMUM_PATIENCE: f32 = 3.0;
+
+
+/// Decides how long to think about a movement given the state of the
+/// clock, and (consulted between iterations of deepening) whether it's time
+/// to stop.
+#[derive(Debug, Clone, Copy)]
+pub struct Hourglass {
+    /// the time we'd like to take, all else being equal
+    pub soft: Duration,
+    /// the time we must not exceed, come what may
+    pub hard: Duration,
+    patience: f32,
+    previously: Option<(Patch, Score)>,
+}
+
+impl Hourglass {
+    pub fn new(remaining: Duration, increment: Duration,
+               movements_to_go: Option<u32>) -> Self {
+        let remaining_ms = remaining.num_milliseconds();
+        let increment_ms = increment.num_milliseconds().max(0);
+        let horizon = movements_to_go
+            .map(i64::from)
+            .unwrap_or(PRESUMED_MOVEMENTS_TO_GO)
+            .max(1);
+        let usable_ms = (remaining_ms - OVERHEAD_MILLISECONDS).max(1);
+        let hard_ms = ((usable_ms as f32 * HARD_SHARE_OF_REMAINING) as i64)
+            .max(1);
+        let soft_ms = (usable_ms / horizon + increment_ms * 3 / 4)
+            .min(hard_ms)
+            .max(1);
+        let soft = Duration::milliseconds(soft_ms);
+        Self {
+            soft,
+            hard: Duration::milliseconds(hard_ms).min(soft * HARD_MULTIPLE_OF_SOFT),
+            patience: 1.0,
+            previously: None,
+        }
+    }
+
+    /// Having spent `elapsed` so far, with the latest iteration preferring
+    /// `best` (scoring `score`) and the runner-up scoring `runner_up` (if
+    /// there is one), should we search another ply deeper?
+    pub fn persevere(&mut self, elapsed: Duration, best: Patch, score: Score,
+                     runner_up: Option<Score>) -> bool {
+        let runner_up_score = match runner_up {
+            Some(s) => s,
+            // no need to deliberate over the only movement there is
+            None => return false,
+        };
+        let mut patience = 1.0;
+        if let Some((previous_best, previous_score)) = self.previously {
+            if previous_best != best {
+                patience *= INSTABILITY_FACTOR;
+            } else if score - runner_up_score > DOMINANCE_MARGIN {
+                patience *= DOMINANCE_FACTOR;
+            }
+            if score < previous_score - SCORE_DROP_MARGIN {
+                patience *= SCORE_DROP_FACTOR;
+            }
+        }
+        // instability lingers, but fades
+        self.patience = (0.5 * (self.patience + patience)).min(MAXIMUM_PATIENCE);
+        self.previously = Some((best, score));
+
+        let allotment_ms =
+            (self.soft.num_milliseconds() as f32 * self.patience) as i64;
+        // the next iteration will take a good deal longer than all of the
+        // previous ones together, so don't start it if we're halfway spent
+        elapsed.num_milliseconds() * 2 < allotment_ms &&
+            elapsed < self.hard
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+
+    use super::Hourglass;
+    use karma::Score;
+    use identity::{Agent, JobDescription, Team};
+    use life::Patch;
+    use space::Locale;
+
+    fn patch(whither: &str) -> Patch {
+        Patch {
+            star: Agent::new(Team::Orange, JobDescription::Pony),
+            whence: Locale::from_algebraic("g1"),
+            whither: Locale::from_algebraic(whither),
+        }
+    }
+
+    #[test]
+    fn concerning_allotments() {
+        // forty moves in ten minutes
+        let hourglass = Hourglass::new(
+            Duration::minutes(10), Duration::zero(), Some(40));
+        assert!(hourglass.soft > Duration::seconds(14));
+        assert!(hourglass.soft < Duration::seconds(15));
+        assert!(hourglass.hard > hourglass.soft);
+
+        // with no `movestogo`, we still budget something sensible
+        let hourglass = Hourglass::new(
+            Duration::seconds(60), Duration::seconds(1), None);
+        assert!(hourglass.soft > Duration::seconds(1));
+        assert!(hourglass.soft < Duration::seconds(4));
+
+        // and in desperate straits, we never plan to overstep the clock
+        let hourglass = Hourglass::new(
+            Duration::milliseconds(300), Duration::zero(), Some(1));
+        assert!(hourglass.hard < Duration::milliseconds(300));
+        assert!(hourglass.soft <= hourglass.hard);
+        let hourglass = Hourglass::new(
+            Duration::milliseconds(-20), Duration::zero(), None);
+        assert!(hourglass.soft > Duration::zero());
+    }
+
+    #[test]
+    fn concerning_perseverance() {
+        let mut steady = Hourglass::new(
+            Duration::minutes(5), Duration::zero(), Some(30));
+        assert!(steady.persevere(Duration::seconds(1), patch("f3"), Score(50),
+                                 Some(Score(30))));
+        assert!(steady.persevere(Duration::seconds(2), patch("f3"), Score(50),
+                                 Some(Score(30))));
+        assert!(!steady.persevere(Duration::seconds(6), patch("f3"), Score(50),
+                                  Some(Score(30))));
+
+        // but we'll think longer about a world we keep changing our mind
+        // about
+        let mut fickle = Hourglass::new(
+            Duration::minutes(5), Duration::zero(), Some(30));
+        fickle.persevere(Duration::seconds(1), patch("f3"), Score(50),
+                         Some(Score(30)));
+        assert!(fickle.persevere(Duration::seconds(6), patch("h3"), Score(50),
+                                 Some(Score(30))));
+
+        // and less about one where the choice is obvious
+        let mut decisive = Hourglass::new(
+            Duration::minutes(5), Duration::zero(), Some(30));
+        decisive.persevere(Duration::seconds(1), patch("e5"), Score(900),
+                           Some(Score(50)));
+        assert!(!decisive.persevere(Duration::seconds(4), patch("e5"), Score(900),
+                                    Some(Score(50))));
+
+        // or where there is no choice at all
+        let mut forced = Hourglass::new(
+            Duration::minutes(5), Duration::zero(), Some(30));
+        assert!(!forced.persevere(Duration::milliseconds(1), patch("f3"), Score(0),
+                                  None));
+    }
+}
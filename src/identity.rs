@@ -42,6 +42,20 @@ pub enum JobDescription {
     Figurehead, // ♂
 }
 
+impl JobDescription {
+    /// how much a figurine counts towards the phase of the world: a pony or
+    /// scholar 1, a cop 2, a princess 4, and servants and figureheads not at
+    /// all
+    pub fn phase_weight(self) -> i32 {
+        match self {
+            JobDescription::Servant | JobDescription::Figurehead => 0,
+            JobDescription::Pony | JobDescription::Scholar => 1,
+            JobDescription::Cop => 2,
+            JobDescription::Princess => 4,
+        }
+    }
+}
+
 #[derive(Eq,PartialEq,Debug,Copy,Clone,Hash,RustcEncodable,RustcDecodable)]
 pub struct Agent {
     pub team: Team,
@@ -0,0 +1,486 @@
+use identity::{Agent, JobDescription, Team};
+use karma::Score;
+use life::{coplike_sweep, scholarlike_sweep, servant_strikes, WorldState};
+use landmark::{ADJACENT_FILES, FILES, HIGH_PASSED_SPANS, LOW_PASSED_SPANS};
+use motion::{FIGUREHEAD_MOVEMENT_TABLE, PONY_MOVEMENT_TABLE};
+use space::{Locale, Pinfield};
+
+
+/// What each locale that a figurine can safely reach is worth (in hundredths
+/// of a servant) in the middlegame and the endgame. Servants and figureheads
+/// don't count: servants can hardly go anywhere anyway, and the figurehead's
+/// freedom is a matter for his safety.
+fn mobility_weights(job_description: JobDescription) -> (i32, i32) {
+    match job_description {
+        JobDescription::Pony => (4, 4),
+        JobDescription::Scholar => (5, 5),
+        JobDescription::Cop => (2, 4),
+        JobDescription::Princess => (1, 2),
+        JobDescription::Servant | JobDescription::Figurehead => (0, 0),
+    }
+}
+
+/// the locales that an officer at `whence` could move to or stun on (own
+/// figurines included)
+fn reach(job_description: JobDescription, whence: Locale,
+         occupied: Pinfield) -> Pinfield {
+    match job_description {
+        JobDescription::Pony => {
+            Pinfield(PONY_MOVEMENT_TABLE[whence.pindex() as usize])
+        }
+        JobDescription::Scholar => scholarlike_sweep(whence, occupied),
+        JobDescription::Cop => coplike_sweep(whence, occupied),
+        JobDescription::Princess => {
+            scholarlike_sweep(whence, occupied)
+                .union(coplike_sweep(whence, occupied))
+        }
+        JobDescription::Servant | JobDescription::Figurehead => Pinfield::new(),
+    }
+}
+
+/// How freely a team's officers can move in the middlegame and in the
+/// endgame, counting the locales they can reach that aren't occupied by
+/// friends or struck at by opposing servants.
+pub fn freedom(world: &WorldState, team: Team) -> (Score, Score) {
+    let occupied = world.occupied();
+    let opposing_servants = *world.agent_to_pinfield_ref(
+        Agent::new(team.opposition(), JobDescription::Servant));
+    let unsafe_or_ours = world.occupied_by(team)
+        .union(servant_strikes(team.opposition(), opposing_servants));
+    let mut middlegame = 0;
+    let mut endgame = 0;
+    for agent in &Agent::dramatis_personæ(team) {
+        let (middlegame_weight, endgame_weight) =
+            mobility_weights(agent.job_description);
+        if middlegame_weight == 0 && endgame_weight == 0 {
+            continue;
+        }
+        let mut bits = world.agent_to_pinfield_ref(*agent).0;
+        while bits != 0 {
+            let pindex = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+            let whence = Locale::new(pindex / 8, pindex % 8);
+            let freedom = i32::from(
+                reach(agent.job_description, whence, occupied)
+                    .intersection(unsafe_or_ours.invert())
+                    .pincount());
+            middlegame += middlegame_weight * freedom;
+            endgame += endgame_weight * freedom;
+        }
+    }
+    (Score(middlegame), Score(endgame))
+}
+
+/// How much more freely Orange's officers can move than Blue's, in the
+/// middlegame and in the endgame.
+pub fn mobility(world: &WorldState) -> (Score, Score) {
+    let (orange_middlegame, orange_endgame) = freedom(world, Team::Orange);
+    let (blue_middlegame, blue_endgame) = freedom(world, Team::Blue);
+    (orange_middlegame - blue_middlegame, orange_endgame - blue_endgame)
+}
+
+// Servants standing one and two ranks ahead of a figurehead who has gone
+// into the secret service (on the files around him) keep him out of harm's
+// way ...
+const SHIELD_NEAR: i32 = 20;
+const SHIELD_FAR: i32 = 10;
+// ... and a file next to him without any servants, or without any of his
+// own, is a highway for opposing cops.
+const OPEN_FILE_PENALTY: i32 = 25;
+const HALF_OPEN_FILE_PENALTY: i32 = 15;
+// per locale that a princess standing where the figurehead is could reach,
+// while the opposition still has a princess to make use of such lines
+const EXPOSURE_PENALTY: i32 = 3;
+
+/// how much an opposing officer bearing on the locales around the
+/// figurehead threatens him
+fn attack_weight(job_description: JobDescription) -> i32 {
+    match job_description {
+        JobDescription::Pony | JobDescription::Scholar => 20,
+        JobDescription::Cop => 40,
+        JobDescription::Princess => 80,
+        JobDescription::Servant | JobDescription::Figurehead => 0,
+    }
+}
+
+// A lone attacker is rarely dangerous, but they get more dangerous together:
+// the percentage of the attack weight that counts, by number of attackers.
+static ATTACK_SCALING: [i32; 8] = [0, 0, 50, 75, 88, 94, 97, 99];
+
+/// how safe `team`'s figurehead is, in hundredths of a servant
+fn safety(world: &WorldState, team: Team, occupied: Pinfield) -> i32 {
+    let figurehead = world.agent_to_pinfield_ref(
+        Agent::new(team, JobDescription::Figurehead)).0;
+    if figurehead == 0 {
+        return 0;
+    }
+    let pindex = figurehead.trailing_zeros() as u8;
+    let (rank, file) = (pindex / 8, pindex % 8);
+    let (home_rank, forward) = match team {
+        Team::Orange => (0, 1),
+        Team::Blue => (7, -1),
+    };
+    let our_servants = *world.agent_to_pinfield_ref(
+        Agent::new(team, JobDescription::Servant));
+    let their_servants = *world.agent_to_pinfield_ref(
+        Agent::new(team.opposition(), JobDescription::Servant));
+    let neighborhood = file.saturating_sub(1)..(file + 2).min(8);
+    let mut safety = 0;
+
+    // the shield only means anything once he's gone off to a wing
+    if rank == home_rank && (file <= 2 || file >= 5) {
+        for shield_file in neighborhood.clone() {
+            for &(distance, bonus) in &[(1, SHIELD_NEAR), (2, SHIELD_FAR)] {
+                let shield_rank = (i16::from(rank) + forward * distance) as u8;
+                if our_servants.query(Locale::new(shield_rank, shield_file)) {
+                    safety += bonus;
+                    break;
+                }
+            }
+        }
+    }
+
+    for neighboring_file in neighborhood {
+        let lane = Pinfield(FILES[neighboring_file as usize]);
+        if lane.intersection(our_servants).pincount() == 0 {
+            if lane.intersection(their_servants).pincount() == 0 {
+                safety -= OPEN_FILE_PENALTY;
+            } else {
+                safety -= HALF_OPEN_FILE_PENALTY;
+            }
+        }
+    }
+
+    let whence = Locale::new(rank, file);
+    let zone = Pinfield(FIGUREHEAD_MOVEMENT_TABLE[pindex as usize] | figurehead);
+    let mut attackers = 0;
+    let mut attack = 0;
+    for agent in &Agent::dramatis_personæ(team.opposition()) {
+        let weight = attack_weight(agent.job_description);
+        if weight == 0 {
+            continue;
+        }
+        let mut bits = world.agent_to_pinfield_ref(*agent).0;
+        while bits != 0 {
+            let attacker_pindex = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+            let attacker_whence =
+                Locale::new(attacker_pindex / 8, attacker_pindex % 8);
+            if reach(agent.job_description, attacker_whence, occupied)
+                .intersection(zone).pincount() > 0 {
+                attackers += 1;
+                attack += weight;
+            }
+        }
+    }
+    safety -= attack * ATTACK_SCALING[attackers.min(7)] / 100;
+
+    let opposing_princesses = world.agent_to_pinfield_ref(
+        Agent::new(team.opposition(), JobDescription::Princess));
+    if opposing_princesses.pincount() > 0 {
+        let exposure = reach(JobDescription::Princess, whence, occupied)
+            .intersection(world.occupied_by(team).invert())
+            .pincount();
+        safety -= EXPOSURE_PENALTY * i32::from(exposure);
+    }
+    safety
+}
+
+/// how safe a team's figurehead is
+pub fn shelter(world: &WorldState, team: Team) -> Score {
+    Score(safety(world, team, world.occupied()))
+}
+
+/// How much safer Orange's figurehead is than Blue's. (This only matters
+/// while there are officers about to threaten them, so it's a middlegame
+/// term.)
+pub fn figurehead_safety(world: &WorldState) -> Score {
+    let occupied = world.occupied();
+    Score(safety(world, Team::Orange, occupied) -
+          safety(world, Team::Blue, occupied))
+}
+
+
+// What a passed servant is worth in the middlegame and the endgame, by how
+// far he's come (counting from his team's home rank) ...
+static PASSED_BONUS: [(i32, i32); 8] = [
+    (0, 0), (5, 10), (5, 15), (10, 25), (20, 40), (35, 65), (55, 100), (0, 0)];
+// ... and (in the endgame) in addition, if nothing stands in his way.
+static FREE_PATH_BONUS: [i32; 8] = [0, 0, 5, 10, 15, 25, 40, 0];
+// servants beside or behind each other (by rank) watch each other's backs
+static CONNECTED_BONUS: [i32; 8] = [0, 0, 5, 7, 10, 15, 25, 0];
+// a servant with no friends on the files beside him can't be defended by one
+const ISOLATED_PENALTY: (i32, i32) = (10, 15);
+// nor can one whose friends have all gone on ahead, and who can't catch up
+// because opposing servants guard the locale in front of him
+const BACKWARD_PENALTY: (i32, i32) = (8, 10);
+// A passed servant that the opposing figurehead can't catch is as good as a
+// princess (nearly), when neither team has any officers to stop him.
+const UNSTOPPABLE_BONUS: i32 = 500;
+
+/// What the servants' formation is worth (from Orange's perspective), which
+/// depends on nothing but where the servants are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServantStructure {
+    pub middlegame: Score,
+    pub endgame: Score,
+    /// each team's passed servants, Orange's first
+    pub passed: [Pinfield; 2],
+}
+
+fn relative_rank(team: Team, rank: u8) -> usize {
+    match team {
+        Team::Orange => rank as usize,
+        Team::Blue => 7 - rank as usize,
+    }
+}
+
+fn passed_span(team: Team, pindex: usize) -> u64 {
+    match team {
+        Team::Orange => HIGH_PASSED_SPANS[pindex],
+        Team::Blue => LOW_PASSED_SPANS[pindex],
+    }
+}
+
+/// one team's share of the servant structure, with their passed servants
+fn formation(team: Team, ours: Pinfield, theirs: Pinfield) -> (i32, i32, Pinfield) {
+    let mut middlegame = 0;
+    let mut endgame = 0;
+    let mut passed = Pinfield::new();
+    let supported = servant_strikes(team, ours);
+    let their_strikes = servant_strikes(team.opposition(), theirs);
+    let mut bits = ours.0;
+    while bits != 0 {
+        let pindex = bits.trailing_zeros() as usize;
+        bits &= bits - 1;
+        let (rank, file) = ((pindex / 8) as u8, (pindex % 8) as u8);
+        let progress = relative_rank(team, rank);
+        let locale = Locale::new(rank, file);
+
+        if passed_span(team, pindex) & theirs.0 == 0 {
+            passed = passed.alight(locale);
+            middlegame += PASSED_BONUS[progress].0;
+            endgame += PASSED_BONUS[progress].1;
+        }
+
+        let neighbors = ours.0 & ADJACENT_FILES[file as usize];
+        if neighbors == 0 {
+            middlegame -= ISOLATED_PENALTY.0;
+            endgame -= ISOLATED_PENALTY.1;
+            continue;
+        }
+        let abreast = neighbors & (0xff << (8 * rank));
+        if abreast != 0 || supported.query(locale) {
+            middlegame += CONNECTED_BONUS[progress];
+            endgame += CONNECTED_BONUS[progress];
+            continue;
+        }
+        // (the span looking backwards covers the neighbors behind him)
+        let behind = neighbors & passed_span(team.opposition(), pindex);
+        let stop = match team {
+            Team::Orange => (1u64 << pindex) << 8,
+            Team::Blue => (1u64 << pindex) >> 8,
+        };
+        if behind == 0 && their_strikes.0 & stop != 0 {
+            middlegame -= BACKWARD_PENALTY.0;
+            endgame -= BACKWARD_PENALTY.1;
+        }
+    }
+    (middlegame, endgame, passed)
+}
+
+pub fn servant_skeleton(orange_servants: Pinfield, blue_servants: Pinfield)
+                        -> ServantStructure {
+    let (orange_middlegame, orange_endgame, orange_passed) =
+        formation(Team::Orange, orange_servants, blue_servants);
+    let (blue_middlegame, blue_endgame, blue_passed) =
+        formation(Team::Blue, blue_servants, orange_servants);
+    ServantStructure {
+        middlegame: Score(orange_middlegame - blue_middlegame),
+        endgame: Score(orange_endgame - blue_endgame),
+        passed: [orange_passed, blue_passed],
+    }
+}
+
+/// what a team's passed servants can look forward to (in the endgame),
+/// given where everyone else is
+fn prospects(world: &WorldState, team: Team, passed: Pinfield) -> i32 {
+    let occupied = world.occupied();
+    let pure_servant_endgame = world.is_down_to_servants(Team::Orange) &&
+        world.is_down_to_servants(Team::Blue);
+    let their_figurehead = world.agent_to_pinfield_ref(
+        Agent::new(team.opposition(), JobDescription::Figurehead)).0;
+    let mut endgame = 0;
+    let mut bits = passed.0;
+    while bits != 0 {
+        let pindex = bits.trailing_zeros() as usize;
+        bits &= bits - 1;
+        let (rank, file) = (pindex / 8, pindex % 8);
+        let progress = relative_rank(team, rank as u8);
+        let path = passed_span(team, pindex) & FILES[file];
+        if path & occupied.0 != 0 {
+            continue;
+        }
+        endgame += FREE_PATH_BONUS[progress];
+
+        // the rule of the square: can the opposing figurehead get in front
+        // of him in time?
+        if !pure_servant_endgame || their_figurehead == 0 {
+            continue;
+        }
+        let ascension_rank = match team {
+            Team::Orange => 7,
+            Team::Blue => 0,
+        };
+        // (a servant who hasn't moved yet can boost two ranks at once)
+        let mut servant_distance = 7 - progress as i32;
+        if progress == 1 {
+            servant_distance -= 1;
+        }
+        let figurehead_pindex = their_figurehead.trailing_zeros() as i32;
+        let mut figurehead_distance =
+            (figurehead_pindex / 8 - ascension_rank).abs()
+            .max((figurehead_pindex % 8 - file as i32).abs());
+        if world.initiative != team {
+            figurehead_distance -= 1;
+        }
+        if servant_distance < figurehead_distance {
+            endgame += UNSTOPPABLE_BONUS;
+        }
+    }
+    endgame
+}
+
+/// The servant structure of the world in the middlegame and the endgame,
+/// including what becomes of the passed servants given where everyone else
+/// is, given the skeleton of the world's servants (which the search keeps
+/// in a table rather than working out afresh every time).
+pub fn servant_structure(world: &WorldState, skeleton: ServantStructure)
+                         -> (Score, Score) {
+    let endgame = prospects(world, Team::Orange, skeleton.passed[0]) -
+        prospects(world, Team::Blue, skeleton.passed[1]);
+    (skeleton.middlegame, skeleton.endgame + Score(endgame))
+}
+
+/// what a team's servant structure is worth to them in the middlegame and
+/// the endgame
+pub fn servant_formation(world: &WorldState, team: Team) -> (Score, Score) {
+    let ours = *world.agent_to_pinfield_ref(
+        Agent::new(team, JobDescription::Servant));
+    let theirs = *world.agent_to_pinfield_ref(
+        Agent::new(team.opposition(), JobDescription::Servant));
+    let (middlegame, endgame, passed) = formation(team, ours, theirs);
+    (Score(middlegame), Score(endgame + prospects(world, team, passed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{figurehead_safety, mobility, servant_skeleton,
+                servant_structure};
+    use space::Locale;
+    use karma::Score;
+    use life::WorldState;
+
+    #[test]
+    fn concerning_mobility() {
+        assert_eq!((Score(0), Score(0)), mobility(&WorldState::new()));
+
+        // a cop on an open file is worth more than one buried in the corner
+        let buried = mobility(&WorldState::reconstruct(
+            "4k3/8/8/8/8/8/PP6/RN2K3 w - -"));
+        let liberated = mobility(&WorldState::reconstruct(
+            "4k3/8/8/8/8/8/1P6/1N1RK3 w - -"));
+        assert!(buried.0 < liberated.0);
+        assert!(buried.1 < liberated.1);
+
+        // and a pony can't count on locales that servants are guarding
+        let free_pony = mobility(&WorldState::reconstruct(
+            "4k3/8/8/8/4N3/8/8/4K3 w - -"));
+        let hemmed_pony = mobility(&WorldState::reconstruct(
+            "4k3/8/3p1p2/8/4N3/8/8/4K3 w - -"));
+        assert_eq!(Score(8 * 4), free_pony.0);
+        assert_eq!(Score(6 * 4), hemmed_pony.0);
+    }
+
+    #[test]
+    fn concerning_figurehead_safety() {
+        assert_eq!(Score(0), figurehead_safety(&WorldState::new()));
+
+        // a figurehead behind his servants after the secret service ...
+        let sheltered = figurehead_safety(&WorldState::reconstruct(
+            "r3k2r/ppp2ppp/8/8/8/8/PPP2PPP/R4RK1 w kq -"));
+        // ... is better off than one who has let them wander
+        let bared = figurehead_safety(&WorldState::reconstruct(
+            "r3k2r/ppp2ppp/8/8/6PP/8/PPP2P2/R4RK1 w kq -"));
+        assert!(sheltered > bared);
+        // or who has lost one, leaving a file open beside him
+        let unshielded = figurehead_safety(&WorldState::reconstruct(
+            "r3k2r/ppp2pp1/8/8/8/8/PPP2PP1/R4RK1 w kq -"));
+        assert!(sheltered > unshielded);
+
+        // officers bearing down on him are worse together than apart
+        let besieged = figurehead_safety(&WorldState::reconstruct(
+            "6k1/5ppp/8/8/8/5n2/5PPP/4r1K1 w - -"));
+        let harried = figurehead_safety(&WorldState::reconstruct(
+            "6k1/5ppp/8/8/8/8/5PPP/4r1K1 w - -"));
+        assert!(besieged < harried);
+
+        // and opposing princesses make open lines to him dangerous
+        let exposed = figurehead_safety(&WorldState::reconstruct(
+            "6k1/5ppp/8/8/8/8/q7/3K4 w - -"));
+        let unthreatened = figurehead_safety(&WorldState::reconstruct(
+            "6k1/5ppp/8/8/8/8/r7/3K4 w - -"));
+        assert!(exposed < unthreatened);
+    }
+
+    fn structure(world: &WorldState) -> (Score, Score) {
+        servant_structure(
+            world, servant_skeleton(world.orange_servants, world.blue_servants))
+    }
+
+    #[test]
+    fn concerning_servant_structure() {
+        let world = WorldState::new();
+        assert_eq!((Score(0), Score(0)), structure(&world));
+        assert_eq!(0, servant_skeleton(world.orange_servants,
+                                       world.blue_servants).passed[0].pincount());
+
+        // passed, isolated, and more passed the further along he is
+        let world = WorldState::reconstruct("4k3/p7/8/3P4/8/8/8/4K3 w - -");
+        let skeleton = servant_skeleton(world.orange_servants,
+                                        world.blue_servants);
+        assert_eq!(vec![Locale::from_algebraic("d5")],
+                   skeleton.passed[0].to_locales());
+        assert_eq!(vec![Locale::from_algebraic("a7")],
+                   skeleton.passed[1].to_locales());
+        let further = WorldState::reconstruct("4k3/p7/3P4/8/8/8/8/4K3 w - -");
+        assert!(structure(&further).1 > structure(&world).1);
+
+        // connected servants are better than isolated ones
+        let connected = WorldState::reconstruct(
+            "4k3/pp6/8/8/8/8/PP6/4K3 w - -");
+        let disconnected = WorldState::reconstruct(
+            "4k3/pp6/8/8/8/8/P1P5/4K3 w - -");
+        assert!(structure(&connected).0 >
+                structure(&disconnected).0);
+
+        // a backward servant is a weakness
+        let backward = WorldState::reconstruct(
+            "4k3/8/4p3/8/3P4/8/2P5/4K3 w - -");
+        let sound = WorldState::reconstruct(
+            "4k3/8/4p3/8/3P4/2P5/8/4K3 w - -");
+        assert!(structure(&backward).0 < structure(&sound).0);
+
+        // and in a servant endgame, a passed servant outside the opposing
+        // figurehead's square runs away with the game
+        let runaway = WorldState::reconstruct("8/8/6k1/8/P7/8/8/4K3 w - -");
+        let caught = WorldState::reconstruct("8/8/2k5/8/P7/8/8/4K3 w - -");
+        assert!(structure(&runaway).1 >
+                structure(&caught).1 + Score(400));
+        // (but it's a matter of whose turn it is)
+        let in_time = WorldState::reconstruct("8/8/5k2/8/P7/8/8/4K3 b - -");
+        let too_late = WorldState::reconstruct("8/8/5k2/8/P7/8/8/4K3 w - -");
+        assert!(structure(&too_late).1 >
+                structure(&in_time).1 + Score(400));
+    }
+}
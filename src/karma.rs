@@ -0,0 +1,149 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+
+/// How good a world is from some team's perspective, in hundredths of a
+/// servant. The scores within `MATE_HORIZON` of `MATE` (in either direction)
+/// are reserved for forced critical endangerment, and say how many plies
+/// away it is; no evaluation of a world may stray into them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Score(pub i32);
+
+/// The score for stunning the opposing figurehead right now. Being able to
+/// do so `n` plies from now scores `MATE - n`, so that sooner is better.
+pub const MATE: Score = Score(30000);
+// no line of play we search is anywhere near this long
+pub const MATE_HORIZON: i32 = 1000;
+
+/// bounds beyond any score, for opening search windows
+pub const INFINITY: Score = Score(32000);
+pub const NEG_INFINITY: Score = Score(-32000);
+
+
+/// the score for critically endangering the opposing figurehead `ply` plies
+/// after the search started (negate it for being on the receiving end)
+pub fn mating(ply: u8) -> Score {
+    Score(MATE.0 - i32::from(ply))
+}
+
+impl Score {
+    pub fn in_servants(self) -> f32 {
+        self.0 as f32 / 100.
+    }
+
+    pub fn abs(self) -> Self {
+        Score(self.0.abs())
+    }
+
+    pub fn is_mate(self) -> bool {
+        self.0.abs() > MATE.0 - MATE_HORIZON
+    }
+
+    /// If the score means that a team can force critical endangerment, how
+    /// many of their own movements it will take (negative if it's the
+    /// opposing team that can).
+    pub fn mate_in(self) -> Option<i16> {
+        if !self.is_mate() {
+            return None;
+        }
+        let plies = (MATE.0 - self.0.abs()) as i16;
+        if self.0 > 0 {
+            Some((plies + 1) / 2)
+        } else {
+            Some(-plies / 2)
+        }
+    }
+
+    // Mate scores count plies from the start of the search, but the déjà vu
+    // table might serve up a world at a different distance from the start
+    // than where it was remembered, so we remember mate scores as counted
+    // from the world itself, and convert back when recalling them.
+    pub fn moored(self, ply: u8) -> Self {
+        if self.is_mate() {
+            Score(self.0 + self.0.signum() * i32::from(ply))
+        } else {
+            self
+        }
+    }
+
+    pub fn unmoored(self, ply: u8) -> Self {
+        if self.is_mate() {
+            Score(self.0 - self.0.signum() * i32::from(ply))
+        } else {
+            self
+        }
+    }
+}
+
+impl Add for Score {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Score(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Score {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Score {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Score(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Score {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
+impl Neg for Score {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Score(-self.0)
+    }
+}
+
+impl Mul<i32> for Score {
+    type Output = Self;
+    fn mul(self, factor: i32) -> Self {
+        Score(self.0 * factor)
+    }
+}
+
+impl Mul<Score> for i32 {
+    type Output = Score;
+    fn mul(self, score: Score) -> Score {
+        Score(self * score.0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{mating, Score, MATE};
+
+    #[test]
+    fn concerning_mate_scores() {
+        assert_eq!(Some(1), mating(1).mate_in());
+        assert_eq!(Some(2), mating(3).mate_in());
+        assert_eq!(Some(-1), (-mating(2)).mate_in());
+        assert_eq!(None, Score(880).mate_in());
+        assert!(mating(1) > mating(3));
+        assert!(-mating(2) < Score(-20000));
+    }
+
+    #[test]
+    fn concerning_mooring() {
+        // a mate three plies from a world found four plies into the search
+        let found = mating(7);
+        assert_eq!(mating(3), found.moored(4));
+        assert_eq!(mating(5), found.moored(4).unmoored(2));
+        assert_eq!(-mating(5), (-found).moored(4).unmoored(2));
+        assert_eq!(Score(-150), Score(-150).moored(6));
+        assert_eq!(MATE, MATE.unmoored(0));
+    }
+}
@@ -5,12 +5,52 @@ use std::fmt;
 use space::{Locale, RelaxedLocale, Pinfield, ORANGE_FIGUREHEAD_START, BLUE_FIGUREHEAD_START};
 use identity::{Agent, JobDescription, Team};
 use motion::{FIGUREHEAD_MOVEMENT_TABLE, PONY_MOVEMENT_TABLE};
+use landmark::FILES;
 use ansi_term::Colour as Color;
 
 static SCHOLAR_OFFSETS: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
 static COP_OFFSETS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
 
 
+/// the locales reachable from `whence` by sliding along the given offsets,
+/// up to and including the first occupied locale in each direction
+pub fn sweep(whence: Locale, offsets: &[(i8, i8)], occupied: Pinfield) -> Pinfield {
+    let mut swept = Pinfield::new();
+    for &offset in offsets {
+        let mut venture = 1;
+        while let Some(destination) = whence.multidisplace(offset, venture) {
+            swept = swept.alight(destination);
+            if occupied.query(destination) {
+                break;
+            }
+            venture += 1;
+        }
+    }
+    swept
+}
+
+pub fn scholarlike_sweep(whence: Locale, occupied: Pinfield) -> Pinfield {
+    sweep(whence, &SCHOLAR_OFFSETS, occupied)
+}
+
+pub fn coplike_sweep(whence: Locale, occupied: Pinfield) -> Pinfield {
+    sweep(whence, &COP_OFFSETS, occupied)
+}
+
+/// the locales on which a team's servants could stun something, were there
+/// anything there to stun
+pub fn servant_strikes(team: Team, servants: Pinfield) -> Pinfield {
+    // (a servant can't strike westward from the westernmost file, nor
+    // eastward from the easternmost)
+    let westerly = servants.0 & !FILES[0];
+    let easterly = servants.0 & !FILES[7];
+    match team {
+        Team::Orange => Pinfield((westerly << 7) | (easterly << 9)),
+        Team::Blue => Pinfield((westerly >> 9) | (easterly >> 7)),
+    }
+}
+
+
 /// represents the movement of a figurine
 #[derive(Eq,PartialEq,Debug,Copy,Clone,Hash,RustcEncodable,RustcDecodable)]
 pub struct Patch {
@@ -154,6 +194,51 @@ impl fmt::Display for Commit {
 }
 
 
+/// How many figurines of each job description each team has left, and how
+/// much officership (as `terrain::phase` reckons it) there is in the world,
+/// kept up to date as figurines are stunned and servants ascend so that
+/// evaluation needn't count pins. (What the figurines are worth depends on
+/// the evaluation parameters in play, so material is left to evaluation to
+/// total up from the headcounts.)
+#[derive(Eq,PartialEq,Debug,Copy,Clone,Hash,Default)]
+pub struct Census {
+    headcounts: [[u8; 6]; 2],
+    phase: u8,
+}
+
+impl Census {
+    pub fn headcount(&self, agent: Agent) -> u8 {
+        self.headcounts[agent.team as usize][agent.job_description as usize]
+    }
+
+    /// the team's figurines other than servants and the figurehead
+    pub fn officers(&self, team: Team) -> u8 {
+        let headcounts = &self.headcounts[team as usize];
+        headcounts[JobDescription::Pony as usize] +
+            headcounts[JobDescription::Scholar as usize] +
+            headcounts[JobDescription::Cop as usize] +
+            headcounts[JobDescription::Princess as usize]
+    }
+
+    /// the phase of the world, before it's capped at the opening's
+    pub fn phase(&self) -> i32 {
+        i32::from(self.phase)
+    }
+
+    fn enlist(&mut self, agent: Agent, how_many: u8) {
+        self.headcounts[agent.team as usize][agent.job_description as usize] +=
+            how_many;
+        self.phase += agent.job_description.phase_weight() as u8 * how_many;
+    }
+
+    fn discharge(&mut self, agent: Agent, how_many: u8) {
+        self.headcounts[agent.team as usize][agent.job_description as usize] -=
+            how_many;
+        self.phase -= agent.job_description.phase_weight() as u8 * how_many;
+    }
+}
+
+
 #[derive(Eq,PartialEq,Debug,Copy,Clone,Hash)]
 pub struct WorldState {
     pub initiative: Team,
@@ -173,6 +258,10 @@ pub struct WorldState {
     pub blue_figurehead: Pinfield,
     pub service_eligibility: u8,
     pub passing_by_locale: Option<Locale>,
+
+    /// (which anyone setting the pinfields directly, rather than by
+    /// applying patches or replacing subboards, must `recount`)
+    census: Census,
 }
 
 const ORANGE_WEST_ELIGIBILITY: u8 = 0b1;
@@ -189,7 +278,7 @@ impl Default for WorldState {
             orange_servant_locales.push(Locale::new(1, f));
             blue_servant_locales.push(Locale::new(6, f));
         }
-        WorldState {
+        let mut world = WorldState {
             initiative: Team::Orange,
 
             orange_servants: Pinfield::init(&orange_servant_locales),
@@ -210,7 +299,10 @@ impl Default for WorldState {
             blue_figurehead: Pinfield::init(&[BLUE_FIGUREHEAD_START]),
             service_eligibility: 0b1111,
             passing_by_locale: None,
-        }
+            census: Census::default(),
+        };
+        world.recount();
+        world
     }
 }
 
@@ -238,7 +330,27 @@ impl WorldState {
             blue_figurehead: Pinfield::new(),
             service_eligibility: 0,
             passing_by_locale: None,
+            census: Census::default(),
+        }
+    }
+
+    pub fn census(&self) -> &Census {
+        &self.census
+    }
+
+    fn count_heads(&self) -> Census {
+        let mut census = Census::default();
+        for team in Team::league() {
+            for agent in &Agent::dramatis_personæ(team) {
+                census.enlist(*agent, self.agent_to_pinfield_ref(*agent).pincount());
+            }
         }
+        census
+    }
+
+    /// Take the census afresh from the pinfields.
+    pub fn recount(&mut self) {
+        self.census = self.count_heads();
     }
 
     pub fn agent_to_pinfield_ref(&self, agent: Agent) -> &Pinfield {
@@ -425,6 +537,41 @@ impl WorldState {
         self.set_service_eligibility(Team::Blue, true);
     }
 
+    /// The same world with the teams' roles exchanged: every figurine
+    /// changes sides and crosses to the corresponding locale on the other
+    /// side of the world, the eligibilities and the passing-by locale follow
+    /// them, and the initiative passes to the other team. Whatever is good
+    /// for one team in a world is just as good for the other in its mirror
+    /// image.
+    #[cfg(test)] // (for now, just for checking our evenhandedness)
+    pub fn mirrored(&self) -> Self {
+        let mut mirror = WorldState::new_except_empty();
+        mirror.initiative = self.initiative.opposition();
+        for team in Team::league() {
+            for agent in &Agent::dramatis_personæ(team) {
+                let counterpart = Agent::new(team.opposition(),
+                                             agent.job_description);
+                *mirror.agent_to_pinfield_mutref(counterpart) =
+                    self.agent_to_pinfield_ref(*agent).mirrored();
+            }
+        }
+        if self.orange_west_service_eligibility() {
+            mirror.set_blue_west_service_eligibility();
+        }
+        if self.orange_east_service_eligibility() {
+            mirror.set_blue_east_service_eligibility();
+        }
+        if self.blue_west_service_eligibility() {
+            mirror.set_orange_west_service_eligibility();
+        }
+        if self.blue_east_service_eligibility() {
+            mirror.set_orange_east_service_eligibility();
+        }
+        mirror.passing_by_locale = self.passing_by_locale.map(|l| l.mirrored());
+        mirror.recount();
+        mirror
+    }
+
     pub fn reconstruct(scan: &str) -> Self {
         let mut rank = 7;
         let mut file = 0;
@@ -510,11 +657,19 @@ impl WorldState {
                                     -> Self {
         let mut resultant_state = *self;
         resultant_state.agent_to_pinfield_mutref(for_whom).0 = subboard.0;
+        let before = self.agent_to_pinfield_ref(for_whom).pincount();
+        let after = subboard.pincount();
+        if after > before {
+            resultant_state.census.enlist(for_whom, after - before);
+        } else {
+            resultant_state.census.discharge(for_whom, before - after);
+        }
         resultant_state
     }
 
     fn replace_subboard(&mut self, for_whom: Agent, subboard: Pinfield) {
         // note: this mutates in place, which is a bit dangerous. be careful out there!
+        // (and it leaves the census to the caller)
         self.agent_to_pinfield_mutref(for_whom).0 = subboard.0;
     }
 
@@ -567,12 +722,17 @@ impl WorldState {
     }
 
     pub fn apply(&self, patch: Patch) -> Commit {
+        debug_assert!(self.census == self.count_heads(),
+                      "the census is out of date (were the pinfields set \
+                       without a recount?)");
         // subboard of moving figurine
         let backstory = self.agent_to_pinfield_ref(patch.star);
         // subboard of moving figurine after move
         let derived_subboard = backstory.transit(patch.whence, patch.whither);
-        // insert subboard into post-patch world-model
-        let mut tree = self.except_replaced_subboard(patch.star, derived_subboard);
+        // insert subboard into post-patch world-model (where no one's been
+        // stunned yet, so the census stands)
+        let mut tree = *self;
+        tree.replace_subboard(patch.star, derived_subboard);
         match patch.star.job_description {
             JobDescription::Figurehead => {
                 match patch.star.team {
@@ -654,6 +814,7 @@ impl WorldState {
             let further_derived_subboard = tree.agent_to_pinfield_ref(stunned)
                                                .quench(ambulance_target);
             tree.replace_subboard(stunned, further_derived_subboard);
+            tree.census.discharge(stunned, 1);
         }
 
         tree.initiative = opposition;
@@ -676,17 +837,15 @@ impl WorldState {
     }
 
     pub fn in_critical_endangerment(&self, team: Team) -> bool {
-        let mut contingency = *self;
-        contingency.initiative = team.opposition();
-        let premonitions = contingency.reckless_lookahead();
-        for premonition in &premonitions {
-            if let Some(patient) = premonition.hospitalization {
-                if patient.job_description == JobDescription::Figurehead {
-                    return true;
-                }
-            }
-        }
-        false
+        let figurehead = self.agent_to_pinfield_ref(
+            Agent::new(team, JobDescription::Figurehead));
+        let occupied = self.occupied();
+        let opposition = self.occupied_by(team.opposition());
+        figurehead.to_locales().iter().any(|&locale| {
+            self.attackers_of(locale, occupied)
+                .intersection(opposition)
+                .pincount() > 0
+        })
     }
 
     pub fn careful_apply(&self, patch: Patch) -> Option<Commit> {
@@ -698,6 +857,59 @@ impl WorldState {
         }
     }
 
+    /// the world as it would be if the team with the initiative declined to
+    /// move (which the rules don't actually allow, but which is useful for
+    /// the search to imagine)
+    pub fn null_move(&self) -> Self {
+        let mut tree = *self;
+        tree.initiative = self.initiative.opposition();
+        // the opportunity to stun a servant in passing only lasts for the
+        // one turn immediately after his boost
+        tree.passing_by_locale = None;
+        tree
+    }
+
+    /// whether the team's only figurines are servants (and the figurehead),
+    /// in which case being obliged to move can actually be a liability
+    pub fn is_down_to_servants(&self, team: Team) -> bool {
+        self.census.officers(team) == 0
+    }
+
+    /// the figurines (of either team) which could stun something at
+    /// `locale`, pretending that only the figurines on `occupied` are
+    /// present—which lets us see through figurines that have already
+    /// moved away, as when playing out a sequence of stunnings
+    pub fn attackers_of(&self, locale: Locale, occupied: Pinfield) -> Pinfield {
+        let mut attackers = Pinfield::new();
+        // servants stun diagonally forward, so a servant endangering this
+        // locale is diagonally backward from it
+        for &(rank_offset, servants) in &[(-1, self.orange_servants),
+                                           (1, self.blue_servants)] {
+            for &file_offset in &[-1, 1] {
+                if let Some(whence) = locale.displace((rank_offset, file_offset)) {
+                    if servants.query(whence) {
+                        attackers = attackers.alight(whence);
+                    }
+                }
+            }
+        }
+        let pindex = locale.pindex() as usize;
+        attackers = attackers
+            .union(Pinfield(PONY_MOVEMENT_TABLE[pindex])
+                   .intersection(self.orange_ponies.union(self.blue_ponies)))
+            .union(Pinfield(FIGUREHEAD_MOVEMENT_TABLE[pindex])
+                   .intersection(self.orange_figurehead
+                                     .union(self.blue_figurehead)));
+        let princesses = self.orange_princesses.union(self.blue_princesses);
+        let scholarlike = self.orange_scholars.union(self.blue_scholars)
+            .union(princesses);
+        let coplike = self.orange_cops.union(self.blue_cops).union(princesses);
+        attackers = attackers
+            .union(scholarlike_sweep(locale, occupied).intersection(scholarlike))
+            .union(coplike_sweep(locale, occupied).intersection(coplike));
+        attackers.intersection(occupied)
+    }
+
     fn subpredict(&self, premonitions: &mut Vec<Commit>, premonition: Commit) {
         if premonition.patch.concerns_servant_ascension() {
             for ascended in &Agent::dramatis_personæ(premonition.patch.star.team) {
@@ -715,9 +927,11 @@ impl WorldState {
                                                    .agent_to_pinfield_ref(*ascended)
                                                    .alight(premonition.patch
                                                                       .whither);
-                ascendency.tree = ascendency.tree.except_replaced_subboard(premonition.patch.star,
-                                                                            vessel_pinfield);
+                ascendency.tree.replace_subboard(premonition.patch.star,
+                                                 vessel_pinfield);
                 ascendency.tree.replace_subboard(*ascended, ascended_pinfield);
+                ascendency.tree.census.discharge(premonition.patch.star, 1);
+                ascendency.tree.census.enlist(*ascended, 1);
                 premonitions.push(ascendency);
             }
         } else {
@@ -1132,7 +1346,7 @@ mod tests {
     extern crate test;
     use std::mem;
     use self::test::{Bencher, black_box};
-    use super::{WorldState, Patch, Commit};
+    use super::{WorldState, Patch, Commit, servant_strikes};
     use space::Locale;
     use identity::{Team, JobDescription, Agent};
 
@@ -1443,6 +1657,7 @@ mod tests {
         world.blue_princesses =
             world.blue_princesses
                  .alight(Locale::from_algebraic("g3"));
+        world.recount();
         let mut premonitions = Vec::new();
         world.scholar_lookahead(Team::Orange, false, &mut premonitions);
         let expected = vec!["d2", "f2", "g3"]
@@ -1593,6 +1808,38 @@ mod tests {
         assert!(!v_day.in_critical_endangerment(Team::Blue));
     }
 
+    #[test]
+    fn concerning_critical_endangerment_by_attackers() {
+        // asking which figurines could stun the figurehead should agree with
+        // asking whether any movement the opposition could make would
+        let endangered_by_lookahead = |world: WorldState, team: Team| {
+            let mut contingency = world;
+            contingency.initiative = team.opposition();
+            contingency.reckless_lookahead().iter().any(|c| {
+                c.hospitalization.map_or(false, |patient| {
+                    patient.job_description == JobDescription::Figurehead
+                })
+            })
+        };
+        for &runes in &[
+                "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -",
+                "3q1rk1/2R1bppp/pP2p3/N2b4/1r6/4BP2/1P1Q2PP/R5K1 b - -",
+                "8/8/3k4/2P1p3/3K4/8/8/8 w - -",
+                "4k3/8/3N4/8/8/8/8/4K3 b - -",
+                "4k3/8/8/8/8/8/8/R3K2R w KQ -",
+                "7K/r7/1r6/8/8/8/8/7k b - -"] {
+            let world = WorldState::reconstruct(runes);
+            for premonition in world.reckless_lookahead() {
+                for &team in &[Team::Orange, Team::Blue] {
+                    assert_eq!(
+                        endangered_by_lookahead(premonition.tree, team),
+                        premonition.tree.in_critical_endangerment(team),
+                        "{:?} in {}", team, premonition.tree.preserve());
+                }
+            }
+        }
+    }
+
     #[test]
     fn concerning_fools_assasination() {
         let world = death_of_a_fool();
@@ -1656,6 +1903,87 @@ mod tests {
         assert_eq!(None, world.passing_by_locale);
     }
 
+    #[test]
+    fn concerning_passing_the_initiative() {
+        let world = WorldState::reconstruct(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3");
+        let passed = world.null_move();
+        assert_eq!(Team::Orange, passed.initiative);
+        assert_eq!(None, passed.passing_by_locale);
+        assert_eq!("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq -",
+                   passed.preserve());
+        assert_eq!(world.occupied(), passed.occupied());
+    }
+
+    #[test]
+    fn concerning_servant_only_endgames() {
+        let world = WorldState::reconstruct("4k3/pp6/8/8/8/8/PP6/4K1N1 w - -");
+        assert!(!world.is_down_to_servants(Team::Orange));
+        assert!(world.is_down_to_servants(Team::Blue));
+    }
+
+    #[test]
+    fn concerning_the_census() {
+        let world = WorldState::new();
+        assert_eq!(8, world.census().headcount(
+            Agent::new(Team::Blue, JobDescription::Servant)));
+        assert_eq!(7, world.census().officers(Team::Orange));
+        assert_eq!(24, world.census().phase());
+
+        // however the world changes—by stunning, stunning in passing, the
+        // secret service, or ascension (with or without a stunning)—the
+        // census should agree with a fresh count
+        for runes in &["r3k2r/1P4pp/8/3pP3/8/8/8/R3K2R w KQkq d6",
+                       "rnbqkbnr/ppp1pppp/8/8/2Pp4/4P3/PP1P1PPP/RNBQKBNR b KQkq c3",
+                       "1n2k3/P1Pq4/8/8/8/8/8/4K3 w - -"] {
+            let world = WorldState::reconstruct(runes);
+            for premonition in world.lookahead() {
+                let mut recounted = premonition.tree;
+                recounted.recount();
+                assert_eq!(recounted.census(), premonition.tree.census());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the census is out of date")]
+    fn concerning_stale_censuses() {
+        let mut world = WorldState::new_except_empty();
+        world.orange_cops = world.orange_cops.alight(Locale::from_algebraic("a1"));
+        world.blue_ponies = world.blue_ponies.alight(Locale::from_algebraic("a8"));
+        // (no recount)
+        world.apply(Patch { star: Agent::new(Team::Orange, JobDescription::Cop),
+                            whence: Locale::from_algebraic("a1"),
+                            whither: Locale::from_algebraic("a8") });
+    }
+
+    #[test]
+    fn concerning_attackers() {
+        let world = WorldState::reconstruct("r6k/p7/1P6/8/8/8/R7/R6K w - -");
+        let a7 = Locale::from_algebraic("a7");
+        assert_eq!(vec![Locale::from_algebraic("a2"),
+                        Locale::from_algebraic("b6"),
+                        Locale::from_algebraic("a8")],
+                   world.attackers_of(a7, world.occupied()).to_locales());
+        // with the front cop out of the way, the one behind him joins in
+        let clearing = world.occupied().quench(Locale::from_algebraic("a2"));
+        assert!(world.attackers_of(a7, clearing)
+                     .query(Locale::from_algebraic("a1")));
+    }
+
+    #[test]
+    fn concerning_servant_strikes() {
+        let world = WorldState::reconstruct("4k3/7p/8/8/8/8/P3P3/4K3 w - -");
+        assert_eq!(vec![Locale::from_algebraic("b3"),
+                        Locale::from_algebraic("d3"),
+                        Locale::from_algebraic("f3")],
+                   servant_strikes(Team::Orange, world.orange_servants)
+                       .to_locales());
+        assert_eq!(vec![Locale::from_algebraic("g6")],
+                   servant_strikes(Team::Blue, world.blue_servants)
+                       .to_locales());
+    }
+
     #[test]
     fn concerning_passing_by_in_action() {
         let world = WorldState::reconstruct("rnbqkbnr/ppp2ppp/4p3/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3");
@@ -1680,4 +2008,29 @@ mod tests {
 
 
     }
+
+    #[test]
+    fn concerning_mirrors() {
+        let mut blue_to_move = WorldState::new();
+        blue_to_move.initiative = Team::Blue;
+        assert_eq!(blue_to_move, WorldState::new().mirrored());
+
+        let world = WorldState::reconstruct(
+            "r3k2r/ppp2ppp/4p3/3pP3/8/8/PPPP1PPP/R3K1NR w Kkq d6");
+        let mirror = world.mirrored();
+        assert_eq!("r3k1nr/pppp1ppp/8/8/3Pp3/4P3/PPP2PPP/R3K2R b KQk d3",
+                   mirror.preserve());
+        assert_eq!(world, mirror.mirrored());
+
+        // and what can happen in one can happen in the other
+        let mut movements = world.lookahead().iter()
+            .map(|c| c.tree.mirrored().preserve())
+            .collect::<Vec<_>>();
+        let mut mirrored_movements = mirror.lookahead().iter()
+            .map(|c| c.tree.preserve())
+            .collect::<Vec<_>>();
+        movements.sort();
+        mirrored_movements.sort();
+        assert_eq!(movements, mirrored_movements);
+    }
 }
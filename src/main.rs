@@ -28,7 +28,13 @@ mod identity;
 mod motion;
 mod landmark;
 mod life;
+mod karma;
+mod judgment;
+mod terrain;
+mod hourglass;
 mod mind;
+mod scales;
+mod reflection;
 mod substrate;
 mod uci; // Unlikely Command Integration
 mod test_landmark;
@@ -37,17 +43,23 @@ use std::fs::OpenOptions;
 use std::io;
 use std::io::Write;
 use std::process;
+use std::sync::Arc;
+use std::thread;
 
 use ansi_term::Colour as Color;
-use argparse::{ArgumentParser, Print, Store, StoreOption, StoreTrue};
+use argparse::{ArgumentParser, Print, Store, StoreFalse, StoreOption, StoreTrue};
 use log::{LogLevelFilter, LogMetadata, LogRecord, SetLoggerError};
 use rustc_serialize::json;
 use time::{Duration, get_time};
 
 use identity::{Agent, Team};
+use karma::Score;
 use life::{Commit, Patch, TransitPatch, WorldState};
 use mind::{Variation, fixed_depth_sequence_kickoff, iterative_deepening_kickoff,
-           kickoff, pagan_variation_format, Memory};
+           kickoff, leashed_iterative_deepening_kickoff,
+           pagan_variation_format, score_breakdown, Consideration, Curfew,
+           Extensions, Leash, Margins, Memory, SearchParams, SearchStats};
+use scales::EvalParams;
 use substrate::memory_free;
 
 
@@ -88,23 +100,57 @@ impl log::Log for DebugLogger {
 
 #[derive(Debug, Clone)]
 enum LookaheadBound {
-    Depth(u8, Option<u8>),
+    Depth(u8),
     DepthSequence(Vec<u8>),
     Seconds(u8),
+    Milliseconds(u64),
+    Nodes(u64),
+    MateIn(u8),
 }
 
 
 impl LookaheadBound {
-    pub fn duration(&self) -> Duration {
+    pub fn curfew(&self) -> Curfew {
         match *self {
-            LookaheadBound::Seconds(secs) => Duration::seconds(i64::from(secs)),
+            LookaheadBound::Seconds(secs) => Curfew {
+                timeout: Some(Duration::seconds(i64::from(secs))),
+                ..Curfew::default()
+            },
+            LookaheadBound::Milliseconds(millis) => Curfew {
+                timeout: Some(Duration::milliseconds(millis as i64)),
+                ..Curfew::default()
+            },
+            LookaheadBound::Nodes(nodes) => Curfew {
+                nodes: Some(nodes as usize),
+                ..Curfew::default()
+            },
+            LookaheadBound::MateIn(movements) => Curfew {
+                mate_in: Some(movements),
+                ..Curfew::default()
+            },
             _ => {
-                moral_panic!("`duration()` called on non-Seconds LookaheadBound \
-                              variant")
+                moral_panic!("`curfew()` called on depth-bounded \
+                              LookaheadBound variant")
             }
         }
     }
 
+    /// a curfew for iterative deepening that goes about as far as this
+    /// bound would
+    pub fn deepening_curfew(&self) -> Curfew {
+        match *self {
+            LookaheadBound::Depth(depth) => Curfew {
+                depth: Some(depth),
+                ..Curfew::default()
+            },
+            LookaheadBound::DepthSequence(ref depths) => Curfew {
+                depth: depths.iter().max().cloned(),
+                ..Curfew::default()
+            },
+            _ => self.curfew(),
+        }
+    }
+
     pub fn new_from_sequence_depiction(depiction: &str) -> Self {
         let depth_runes = depiction.split(',');
         let depth_sequence = depth_runes.map(|dd| {
@@ -117,16 +163,19 @@ impl LookaheadBound {
     }
 
     pub fn from_args(lookahead_depth: Option<u8>,
-                     lookahead_extension: Option<u8>,
         lookahead_depth_sequence: Option<String>,
-        lookahead_seconds: Option<u8>)
+        lookahead_seconds: Option<u8>,
+        lookahead_milliseconds: Option<u64>,
+        lookahead_nodes: Option<u64>,
+        lookahead_mate: Option<u8>)
                      -> Result<Option<Self>, String> {
         let mut bound = None;
         let confirm_bound_is_none =
             |b: &Option<LookaheadBound>| -> Result<bool, String> {
                 if b.is_some() {
-                    Err("more than one of `--depth`, `--depth-sequence`, or \
-                         `--seconds` was passed"
+                    Err("more than one of `--depth`, `--depth-sequence`, \
+                         `--seconds`, `--milliseconds`, `--nodes`, or \
+                         `--mate-in` was passed"
                             .to_owned())
                 } else {
                     Ok(true)
@@ -134,7 +183,7 @@ impl LookaheadBound {
             };
         if let Some(depth) = lookahead_depth {
             confirm_bound_is_none(&bound)?;
-            bound = Some(LookaheadBound::Depth(depth, lookahead_extension));
+            bound = Some(LookaheadBound::Depth(depth));
         }
         if let Some(sequence_depiction) = lookahead_depth_sequence {
             confirm_bound_is_none(&bound)?;
@@ -145,39 +194,120 @@ impl LookaheadBound {
             confirm_bound_is_none(&bound)?;
             bound = Some(LookaheadBound::Seconds(seconds));
         }
+        if let Some(milliseconds) = lookahead_milliseconds {
+            confirm_bound_is_none(&bound)?;
+            bound = Some(LookaheadBound::Milliseconds(milliseconds));
+        }
+        if let Some(nodes) = lookahead_nodes {
+            confirm_bound_is_none(&bound)?;
+            bound = Some(LookaheadBound::Nodes(nodes));
+        }
+        if let Some(movements) = lookahead_mate {
+            confirm_bound_is_none(&bound)?;
+            bound = Some(LookaheadBound::MateIn(movements));
+        }
         Ok(bound)
     }
 }
 
-fn forecast<T: 'static + Memory>(world: WorldState, bound: LookaheadBound, déjà_vu_bound: f32)
-            -> (Vec<(Commit, f32, T)>, u8, Duration) {
+type Forecasting<T> = (Vec<(Commit, Score, T)>, Vec<u8>, Duration, SearchStats);
+
+fn forecast<T: 'static + Memory>(world: WorldState, bound: LookaheadBound,
+                                 params: SearchParams, déjà_vu_bound: f32)
+            -> Forecasting<T> {
     let start_thinking = get_time();
     let forecasts;
-    let depth;
+    let depths;
+    let stats;
     match bound {
-        LookaheadBound::Depth(ds, es) => {
-            forecasts = kickoff::<T>(&world, ds, es, false, déjà_vu_bound);
-            depth = ds;
+        LookaheadBound::Depth(ds) => {
+            let (fs, ss) = kickoff::<T>(&world, ds, params, false, déjà_vu_bound);
+            forecasts = fs;
+            stats = ss;
+            depths = vec![ds];
         },
         LookaheadBound::DepthSequence(ds) => {
-            depth = *ds.last().unwrap();
-            forecasts = fixed_depth_sequence_kickoff::<T>(
-                &world, ds, false, déjà_vu_bound);
-            // XXX TODO: if we're just returning a number, it should be the
-            // lowest depth, but we should really report all of them
+            depths = ds.clone();
+            let (fs, ss) = fixed_depth_sequence_kickoff::<T>(
+                &world, ds, params, false, déjà_vu_bound);
+            forecasts = fs;
+            stats = ss;
         },
-        LookaheadBound::Seconds(_) => {
-            let (fs, ds) = iterative_deepening_kickoff::<T>(
-                &world, bound.duration(), false, déjà_vu_bound);
+        _ => {
+            let (fs, ds, ss) = iterative_deepening_kickoff::<T>(
+                &world, bound.curfew(), params, false, déjà_vu_bound);
             forecasts = fs;
-            depth = ds;
+            depths = vec![ds];
+            stats = ss;
         }
     }
     let stop_thinking = get_time();
     let thinking_time = stop_thinking - start_thinking;
-    (forecasts, depth, thinking_time)
+    (forecasts, depths, thinking_time, stats)
+}
+
+
+/// Thinking about the world we expect to find ourselves in next, while the
+/// human makes up their mind.
+struct Reverie {
+    world: WorldState,
+    leash: Arc<Leash>,
+    thread: thread::JoinHandle<Forecasting<Variation>>,
 }
 
+impl Reverie {
+    fn begin(world: WorldState, bound: &LookaheadBound, params: SearchParams,
+             déjà_vu_bound: f32) -> Self {
+        let curfew = bound.deepening_curfew();
+        let leash = Arc::new(Leash::from_curfew(&curfew));
+        let travel_leash = leash.clone();
+        let thread = thread::spawn(move || {
+            let start_thinking = get_time();
+            let (forecasts, depth, stats) =
+                leashed_iterative_deepening_kickoff::<Variation>(
+                    &world, curfew, travel_leash, params, false,
+                    déjà_vu_bound);
+            (forecasts, vec![depth], get_time() - start_thinking, stats)
+        });
+        Reverie { world, leash, thread }
+    }
+
+    /// If we were right about what happened next, we already know what to
+    /// think of it (or will soon); otherwise, we put the thought aside.
+    fn awaken(self, world: WorldState) -> Option<Forecasting<Variation>> {
+        if world != self.world {
+            self.leash.yank();
+        }
+        let forecasting = self.thread.join().expect("reverie panicked");
+        if world == self.world {
+            Some(forecasting)
+        } else {
+            None
+        }
+    }
+}
+
+
+/// a consideration of evaluation, in hundredths of a servant
+#[derive(RustcEncodable, RustcDecodable)]
+struct Deliberation {
+    term: String,
+    orange: i32,
+    blue: i32,
+    total: i32,
+}
+
+impl From<Consideration> for Deliberation {
+    fn from(consideration: Consideration) -> Self {
+        let (term, orange, blue, total) = consideration;
+        Deliberation {
+            term: term.to_owned(),
+            orange: orange.0,
+            blue: blue.0,
+            total: total.0,
+        }
+    }
+}
 
 #[derive(RustcEncodable, RustcDecodable)]
 struct Postcard {
@@ -186,8 +316,13 @@ struct Postcard {
     hospitalization: Option<Agent>,
     thinking_time: u64,
     depth: u8,
+    depths: Vec<u8>,
+    mate_in: Option<i16>,
+    stats: SearchStats,
     counterreplies: Vec<TransitPatch>,
     rosetta_stone: String,
+    /// what evaluation makes of the world we've brought about
+    explanation: Vec<Deliberation>,
 }
 
 #[derive(RustcEncodable, RustcDecodable)]
@@ -196,15 +331,17 @@ struct LastMissive {
 }
 
 #[allow(collapsible_if)]
-fn correspondence(reminder: &str, bound: LookaheadBound, déjà_vu_bound: f32)
+fn correspondence(reminder: &str, bound: LookaheadBound, params: SearchParams,
+                  déjà_vu_bound: f32)
                   -> String {
     let in_medias_res = WorldState::reconstruct(reminder);
-    let (mut forecasts, depth, sidereal) = forecast::<Patch>(in_medias_res,
+    let (mut forecasts, depths, sidereal, stats) = forecast::<Patch>(in_medias_res,
                                                     bound,
+                                                    params,
                                                     déjà_vu_bound);
 
     if !forecasts.is_empty() {
-        let (determination, _karma, _variation) = forecasts.swap_remove(0);
+        let (determination, karma, _variation) = forecasts.swap_remove(0);
         // XXX TODO FIXME: this doesn't distinguish amongst ascensions
         // (and we can imagine somewhat contrived situations where only
         // some of them are admissible movements)
@@ -228,9 +365,16 @@ fn correspondence(reminder: &str, bound: LookaheadBound, déjà_vu_bound: f32)
             patch: TransitPatch::from(determination.patch),
             hospitalization: determination.hospitalization,
             thinking_time: sidereal.num_milliseconds() as u64,
-            depth,
+            depth: *depths.last().unwrap(),
+            depths,
+            mate_in: karma.mate_in(),
+            stats,
             counterreplies,
             rosetta_stone: determination.patch.abbreviated_pagan_movement_rune(),
+            explanation: score_breakdown(determination.tree, &params.eval)
+                .into_iter()
+                .map(Deliberation::from)
+                .collect(),
         };
         json::encode(&postcard).unwrap()
     } else {
@@ -244,6 +388,30 @@ fn correspondence(reminder: &str, bound: LookaheadBound, déjà_vu_bound: f32)
 }
 
 
+fn explanation(world: WorldState, eval: &EvalParams) -> String {
+    let mut explanation = format!("{:<20}{:>9}{:>9}{:>9}\n",
+                                  "", "Orange", "Blue", "total");
+    let mut balance = Score(0);
+    for (term, orange, blue, total) in score_breakdown(world, eval) {
+        explanation.push_str(&format!(
+            "{:<20}{:>9.2}{:>9.2}{:>9.2}\n", term, orange.in_servants(),
+            blue.in_servants(), total.in_servants()));
+        balance += total;
+    }
+    explanation.push_str(&format!("{:<38}{:>9.2}", "score",
+                                  balance.in_servants()));
+    explanation
+}
+
+
+fn score_depiction(score: Score) -> String {
+    match score.mate_in() {
+        Some(movements) => format!("mate in {}", movements),
+        None => format!("{:.1}", score.in_servants()),
+    }
+}
+
+
 fn the_end() {
     println!("THE END");
     process::exit(0);
@@ -260,10 +428,24 @@ fn main() {
     // TODO CONSIDER: would argparse's Collect action be cleaner?
     let mut lookahead_depth_sequence: Option<String> = None;
     let mut lookahead_seconds: Option<u8> = None;
+    let mut lookahead_milliseconds: Option<u64> = None;
+    let mut lookahead_nodes: Option<u64> = None;
+    let mut lookahead_mate: Option<u8> = None;
     let mut from_runes: Option<String> = None;
     let mut correspond: bool = false;
+    let mut explain: bool = false;
     let mut uci_dæmon: bool = false;
+    let mut quiescence: bool = true;
+    let mut quiescent_checks: bool = false;
+    let mut extension_budget: f32 = Extensions::default().budget;
+    let mut futility_depth: i8 = Margins::default().maximum_depth;
+    let mut deterministic: bool = false;
+    let mut pondering: bool = false;
     let mut déjà_vu_bound: f32 = 2.0;
+    let mut eval_params_path: Option<String> = None;
+    let mut eval_params_destination: Option<String> = None;
+    let mut tuning_lessons: Option<String> = None;
+    let mut tuning_passes: usize = 100;
     let mut debug_logging: bool = false;
     {
         let mut parser = ArgumentParser::new();
@@ -275,7 +457,39 @@ fn main() {
         parser.refer(&mut lookahead_extension).add_option(
             &["--quiet"],
             StoreOption,
-            "search with quietness extension this deep");
+            "(no longer has any effect: the quietness extension has given \
+             way to quiescence search)");
+        parser.refer(&mut quiescence).add_option(
+            &["--no-quiescence"],
+            StoreFalse,
+            "score the leaves of the search as they stand, without first \
+             settling pending stunnings");
+        parser.refer(&mut quiescent_checks).add_option(
+            &["--quiescent-checks"],
+            StoreTrue,
+            "also consider movements that critically endanger the opposing \
+             figurehead when settling the leaves of the search");
+        parser.refer(&mut extension_budget).add_option(
+            &["--extension-budget"],
+            Store,
+            "search forcing lines of play at most this many plies past the \
+             requested depth (0 to never extend)");
+        parser.refer(&mut futility_depth).add_option(
+            &["--futility-depth"],
+            Store,
+            "within this many plies of the leaves, skip searching worlds \
+             whose static score is hopelessly far from the search window \
+             (0 to always search)");
+        parser.refer(&mut deterministic).add_option(
+            &["--deterministic"],
+            StoreTrue,
+            "search on a single thread, so that the same world always gets \
+             the same forecasts");
+        parser.refer(&mut pondering).add_option(
+            &["--ponder"],
+            StoreTrue,
+            "while waiting for a move to be selected, think ahead about the \
+             one that the AI likes best");
         parser.refer(&mut lookahead_depth_sequence).add_option(
             &["--depth-sequence"],
             StoreOption,
@@ -284,11 +498,30 @@ fn main() {
             &["--seconds"],
             StoreOption,
             "rank moves using AI minimax for about this many seconds");
+        parser.refer(&mut lookahead_milliseconds).add_option(
+            &["--milliseconds"],
+            StoreOption,
+            "rank moves using AI minimax for about this many milliseconds");
+        parser.refer(&mut lookahead_nodes).add_option(
+            &["--nodes"],
+            StoreOption,
+            "rank moves using AI minimax until about this many worlds have \
+             been considered");
+        parser.refer(&mut lookahead_mate).add_option(
+            &["--mate-in"],
+            StoreOption,
+            "rank moves using AI minimax until finding a mate in at most \
+             this many moves, or being sure there isn't one");
         parser.refer(&mut correspond).add_option(
             &["--correspond"],
             StoreTrue,
             "just output the serialization of the AI's top response and \
              legal replies thereto");
+        parser.refer(&mut explain).add_option(
+            &["--explain"],
+            StoreTrue,
+            "just show what evaluation makes of the world given by `--from`, \
+             consideration by consideration");
         parser.refer(&mut uci_dæmon).add_option(
             &["--uci", "--deamon", "--dæmon"],
             StoreTrue,
@@ -303,6 +536,32 @@ fn main() {
             "try to not store more entries in the déjà vu table than fit in \
              this many GiB of memory"
         );
+        parser.refer(&mut eval_params_path).add_option(
+            &["--eval-params"],
+            StoreOption,
+            "weigh the considerations of evaluation as given in this file \
+             (one parameter per line, name and then value, in hundredths of \
+             a servant)"
+        );
+        parser.refer(&mut eval_params_destination).add_option(
+            &["--save-eval-params"],
+            StoreOption,
+            "just write the evaluation parameters in effect to this file"
+        );
+        parser.refer(&mut tuning_lessons).add_option(
+            &["--tune"],
+            StoreOption,
+            "just fit the evaluation parameters to the results of the games \
+             in this file (each line a book of preservation runes followed \
+             by the result), and write them out (to `--save-eval-params` if \
+             given, otherwise to standard output)"
+        );
+        parser.refer(&mut tuning_passes).add_option(
+            &["--tuning-passes"],
+            Store,
+            "when tuning, go over the evaluation parameters at most this \
+             many times for each size of nudge"
+        );
         parser.refer(&mut debug_logging).add_option(
             &["--debug"],
             StoreTrue,
@@ -317,19 +576,67 @@ fn main() {
         DebugLogger::init().expect("couldn't initialize logging?!")
     }
 
+    let mut eval = match eval_params_path {
+        Some(path) => match EvalParams::load(&path) {
+            Ok(eval) => eval,
+            Err(error) => moral_panic!(error),
+        },
+        None => EvalParams::default(),
+    };
+    if let Some(path) = tuning_lessons {
+        let lessons = match reflection::load_lessons(&path) {
+            Ok(lessons) => lessons,
+            Err(error) => moral_panic!(error),
+        };
+        eval = reflection::tune(&Arc::new(lessons), eval, tuning_passes);
+        if eval_params_destination.is_none() {
+            print!("{}", eval);
+            process::exit(0);
+        }
+    }
+    if let Some(destination) = eval_params_destination {
+        if let Err(error) = eval.save(&destination) {
+            moral_panic!(error);
+        }
+        process::exit(0);
+    }
+    let params = SearchParams {
+        quiescence,
+        quiescent_checks,
+        extensions: Extensions {
+            budget: extension_budget,
+            ..Extensions::default()
+        },
+        margins: Margins {
+            maximum_depth: futility_depth,
+            ..Margins::default()
+        },
+        eval,
+        deterministic,
+    };
+
+    if explain {
+        let from = from_runes.expect("`--explain` requires `--from`");
+        println!("{}", explanation(WorldState::reconstruct(&from), &params.eval));
+        process::exit(0);
+    }
+
     if correspond {
         let bound_maybe_result = LookaheadBound::from_args(lookahead_depth,
-                                                           lookahead_extension,
                                                            lookahead_depth_sequence,
-                                                           lookahead_seconds);
+                                                           lookahead_seconds,
+                                                           lookahead_milliseconds,
+                                                           lookahead_nodes,
+                                                           lookahead_mate);
         let bound = match bound_maybe_result {
             Ok(bound_maybe) => {
                 match bound_maybe {
                     Some(bound) => bound,
                     None => {
                         moral_panic!("`--correspond` passed without exactly one \
-                                      of `--depth`, `--depth-sequence`, or \
-                                      `--seconds`")
+                                      of `--depth`, `--depth-sequence`, \
+                                      `--seconds`, `--milliseconds`, \
+                                      `--nodes`, or `--mate-in`")
                     }
                 }
             }
@@ -338,7 +645,7 @@ fn main() {
             }
         };
         let from = from_runes.expect("`--correspond` requires `--from`");
-        println!("{}", correspondence(&from, bound, déjà_vu_bound));
+        println!("{}", correspondence(&from, bound, params, déjà_vu_bound));
         process::exit(0);
     }
 
@@ -369,10 +676,13 @@ fn main() {
         None => WorldState::new(),
     };
     let mut premonitions: Vec<Commit>;
+    let mut reverie: Option<Reverie> = None;
     let bound_maybe = LookaheadBound::from_args(lookahead_depth,
-                                                lookahead_extension,
                                                 lookahead_depth_sequence,
-                                                lookahead_seconds)
+                                                lookahead_seconds,
+                                                lookahead_milliseconds,
+                                                lookahead_nodes,
+                                                lookahead_mate)
                           .unwrap();
     loop {
         match bound_maybe {
@@ -389,25 +699,21 @@ fn main() {
                 }
             }
             Some(ref bound) => {
-                let (our_forecasts, depth, thinking_time) =
-                    forecast::<Variation>(world, bound.clone(), déjà_vu_bound);
+                let (our_forecasts, depths, thinking_time, stats) =
+                    match reverie.take().and_then(|r| r.awaken(world)) {
+                        Some(forecasting) => forecasting,
+                        None => forecast::<Variation>(world, bound.clone(),
+                                                      params, déjà_vu_bound),
+                    };
                 let forecasts = our_forecasts;
                 println!("{}", world);
-                let depth_report = match *bound {
-                    LookaheadBound::Depth(standard, extension) => {
-                        match extension {
-                            Some(quietening) => format!(
-                                "at least {} and up to {}",
-                                standard,
-                                standard + quietening),
-                            None => format!("{}", depth)
-                        }
-                    }
-                    _ => format!("{}", depth),
-                };
                 println!("(scoring alternatives {} levels deep took {} ms)",
-                         depth_report,
+                         depths.iter()
+                             .map(|d| d.to_string())
+                             .collect::<Vec<_>>()
+                             .join(", then "),
                          thinking_time.num_milliseconds());
+                println!("({})", stats);
                 premonitions = Vec::new();
                 for (index, sight) in forecasts.into_iter().enumerate() {
                     let (commit, score, variation) = sight;
@@ -415,7 +721,7 @@ fn main() {
                              index,
                              commit,
                              Color::Purple.bold()
-                                          .paint(&format!("{:.1}", score)),
+                                          .paint(&score_depiction(score)),
                              pagan_variation_format(&variation));
                     premonitions.push(commit);
                 }
@@ -423,6 +729,10 @@ fn main() {
                 if premonitions.is_empty() {
                     the_end();
                 }
+                if pondering {
+                    reverie = Some(Reverie::begin(
+                        premonitions[0].tree, bound, params, déjà_vu_bound));
+                }
             }
         }
 
@@ -458,14 +768,32 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::{LookaheadBound, correspondence};
+    use mind::SearchParams;
 
     #[test]
     fn concerning_correspondence_victory_conditions() {
         let blue_concession = correspondence("R6k/6pp/8/8/8/8/8/8 b - -",
-                                             LookaheadBound::Depth(2, None),
+                                             LookaheadBound::Depth(2),
+                                             SearchParams::default(),
                                              1.0);
         assert_eq!("{\"the_triumphant\":\"Orange\"}".to_owned(),
                    blue_concession);
     }
 
+    #[test]
+    fn concerning_lookahead_bounds_from_args() {
+        match LookaheadBound::from_args(None, None, None, Some(250), None, None) {
+            Ok(Some(LookaheadBound::Milliseconds(250))) => {},
+            _ => panic!("expected a millisecond bound"),
+        }
+        match LookaheadBound::from_args(None, None, None, None, None, Some(2)) {
+            Ok(Some(bound)) => assert_eq!(Some(2), bound.curfew().mate_in),
+            _ => panic!("expected a mate bound"),
+        }
+        assert!(LookaheadBound::from_args(
+            Some(3), None, None, None, Some(10000), None).is_err());
+        assert!(LookaheadBound::from_args(
+            None, None, None, None, None, None).unwrap().is_none());
+    }
+
 }
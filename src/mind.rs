@@ -1,10 +1,9 @@
-use std::f32::{INFINITY, NEG_INFINITY};
-use std::cmp::Ordering;
 use std::default::Default;
 use std::fmt;
 use std::hash::BuildHasherDefault;
-use std::mem;
+use std::mem::size_of;
 use std::sync::Arc;
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
@@ -15,155 +14,819 @@ use parking_lot;
 use twox_hash::XxHash;
 use fnv;
 
+use hourglass::Hourglass;
 use identity::{Agent, JobDescription, Team};
+use judgment::{self, ServantStructure};
+use karma::{mating, Score, INFINITY, MATE, MATE_HORIZON, NEG_INFINITY};
 use life::{Commit, Patch, WorldState};
+use scales::EvalParams;
 use landmark::{CENTER_OF_THE_WORLD, HIGH_COLONELCY, HIGH_SEVENTH_HEAVEN,
                LOW_COLONELCY, LOW_SEVENTH_HEAVEN, FILES};
 use space::{Pinfield, Locale};
 use substrate::Bytes;
+use terrain;
+
+
+// Declining to move and still finding ourselves above β is strong evidence
+// that actually moving would be, too, so long as we search the pass at least
+// this deep.
+const NULL_MOVE_MINIMUM_DEPTH: i8 = 3;
+
+// Quiet movements this late in the ordering get searched less deeply (and
+// searched again properly if they turn out to beat α) ...
+const LATE_MOVE_REDUCTION_MINIMUM_DEPTH: i8 = 3;
+const LATE_MOVE_REDUCTION_MINIMUM_INDEX: usize = 4;
+// ... or, close enough to the leaves, not at all.
+const LATE_MOVE_PRUNING_MAXIMUM_DEPTH: i8 = 2;
+
+// A stunning in quiescence that wouldn't bring us within this much of α even
+// if we got the victim for free isn't worth looking at.
+const DELTA_MARGIN: Score = Score(200);
+
+// consulting the clock at every world would be wasteful
+const LEASH_CLOCK_INTERVAL: usize = 1024;
+
+// we keep track of principal variations this many plies deep (quiescence can
+// go further, but nobody wants to read that much)
+const FORESIGHT_HORIZON: usize = 128;
+
+
+/// knobs governing how the search spends its effort
+#[derive(Debug, Clone, Copy)]
+pub struct SearchParams {
+    /// whether to settle the stunnings, ascensions, and critical
+    /// endangerments pending at the leaves of the search before scoring them
+    pub quiescence: bool,
+    /// whether quiescence should also consider (on its first ply only)
+    /// movements that critically endanger the opposing figurehead
+    pub quiescent_checks: bool,
+    /// how much deeper to look at movements that tend to matter
+    pub extensions: Extensions,
+    /// how hopeless things must look near the leaves to stop looking
+    pub margins: Margins,
+    /// what the leaves are worth
+    pub eval: EvalParams,
+    /// Whether to search the first movements one after another on a single
+    /// thread, rather than racing each other for the déjà vu table and our
+    /// intuitions, so that the same world always gets the same forecasts
+    /// (and statistics).
+    pub deterministic: bool,
+}
+
+impl Default for SearchParams {
+    fn default() -> Self {
+        Self {
+            quiescence: true,
+            quiescent_checks: false,
+            extensions: Extensions::default(),
+            margins: Margins::default(),
+            eval: EvalParams::default(),
+            deterministic: false,
+        }
+    }
+}
+
+
+/// Tallies of what the search got up to, by which to judge how well it's
+/// going about it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq,
+         RustcEncodable, RustcDecodable)]
+pub struct SearchStats {
+    /// worlds visited by the main search
+    pub nodes: u64,
+    /// worlds visited by quiescence
+    pub quiescence_nodes: u64,
+    /// consultations of the déjà vu table ...
+    pub memory_probes: u64,
+    /// ... that found what they were looking for
+    pub memory_hits: u64,
+    /// entries written to the déjà vu table ...
+    pub memory_stores: u64,
+    /// ... in place of an existing entry for the same world and depth
+    pub memory_overwrites: u64,
+    /// consultations of the servant-structure table ...
+    pub servant_probes: u64,
+    /// ... that found what they were looking for
+    pub servant_hits: u64,
+    /// searches cut off by a movement at or above β ...
+    pub beta_cutoffs: u64,
+    /// ... by the very first movement we tried
+    pub first_movement_cutoffs: u64,
+    /// the most movements deep that any line of play (including
+    /// extensions and quiescence) was followed
+    pub selective_depth: u8,
+}
+
+impl SearchStats {
+    pub fn absorb(&mut self, other: SearchStats) {
+        self.nodes += other.nodes;
+        self.quiescence_nodes += other.quiescence_nodes;
+        self.memory_probes += other.memory_probes;
+        self.memory_hits += other.memory_hits;
+        self.memory_stores += other.memory_stores;
+        self.memory_overwrites += other.memory_overwrites;
+        self.servant_probes += other.servant_probes;
+        self.servant_hits += other.servant_hits;
+        self.beta_cutoffs += other.beta_cutoffs;
+        self.first_movement_cutoffs += other.first_movement_cutoffs;
+        self.selective_depth = self.selective_depth.max(other.selective_depth);
+    }
+
+    fn visit(&mut self, ply: u8) {
+        self.nodes += 1;
+        self.selective_depth = self.selective_depth.max(ply);
+    }
+
+    fn quiesce(&mut self, ply: u8) {
+        self.quiescence_nodes += 1;
+        self.selective_depth = self.selective_depth.max(ply);
+    }
+
+    pub fn all_nodes(&self) -> u64 {
+        self.nodes + self.quiescence_nodes
+    }
+
+    pub fn memory_hit_rate(&self) -> f32 {
+        proportion(self.memory_hits, self.memory_probes)
+    }
+
+    pub fn servant_hit_rate(&self) -> f32 {
+        proportion(self.servant_hits, self.servant_probes)
+    }
+
+    pub fn first_movement_cutoff_rate(&self) -> f32 {
+        proportion(self.first_movement_cutoffs, self.beta_cutoffs)
+    }
+}
+
+fn proportion(part: u64, whole: u64) -> f32 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f32 / whole as f32
+    }
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} nodes ({} in quiescence), selective depth {}, \
+                   déjà vu {:.1}% of {} probes ({} stores, {} overwrites), \
+                   servant structures {:.1}% of {} probes, \
+                   {:.1}% of {} cutoffs on the first movement",
+               self.all_nodes(), self.quiescence_nodes, self.selective_depth,
+               100. * self.memory_hit_rate(), self.memory_probes,
+               self.memory_stores, self.memory_overwrites,
+               100. * self.servant_hit_rate(), self.servant_probes,
+               100. * self.first_movement_cutoff_rate(), self.beta_cutoffs)
+    }
+}
 
 
-const REWARD_FOR_INITIATIVE: f32 = 0.5;
+/// Shared by all the threads of a search to tell them when to give up: once
+/// the deadline passes, once they've visited more worlds than the budget
+/// allows between them, or whenever someone yanks it. A search that comes
+/// back after its leash was yanked has nothing meaningful to say.
+#[derive(Debug, Default)]
+pub struct Leash {
+    deadline: parking_lot::Mutex<Option<time::Timespec>>,
+    node_budget: Option<usize>,
+    /// a clock handed to us after the search began (as when the opposition
+    /// makes the movement we were pondering), and when it started running
+    hourglass: parking_lot::Mutex<Option<(time::Timespec, Hourglass)>>,
+    odometer: AtomicUsize,
+    yanked: AtomicBool,
+}
 
+impl Leash {
+    pub fn new(deadline: Option<time::Timespec>, node_budget: Option<usize>)
+               -> Self {
+        Self {
+            deadline: parking_lot::Mutex::new(deadline),
+            node_budget,
+            ..Self::default()
+        }
+    }
+
+    /// A leash to hold a search to the curfew's deadline (or the hard limit
+    /// of its hourglass, whichever is sooner) and node budget.
+    pub fn from_curfew(curfew: &Curfew) -> Self {
+        let timeout = match (curfew.timeout, curfew.hourglass.map(|h| h.hard)) {
+            (Some(timeout), Some(hard)) => Some(timeout.min(hard)),
+            (timeout, hard) => timeout.or(hard),
+        };
+        Self::new(timeout.map(|timeout| time::get_time() + timeout),
+                  curfew.nodes)
+    }
 
-pub fn orientation(team: Team) -> f32 {
+    /// Put an already-running search on the clock, from now on: the
+    /// hourglass's hard limit becomes our deadline, and iterative deepening
+    /// consults it between iterations just as if it had been there from the
+    /// start.
+    pub fn impose(&self, hourglass: Hourglass) {
+        let now = time::get_time();
+        *self.deadline.lock() = Some(now + hourglass.hard);
+        *self.hourglass.lock() = Some((now, hourglass));
+    }
+
+    fn imposition(&self) -> Option<(time::Timespec, Hourglass)> {
+        *self.hourglass.lock()
+    }
+
+    pub fn yank(&self) {
+        self.yanked.store(true, atomic::Ordering::SeqCst);
+    }
+
+    pub fn yanked(&self) -> bool {
+        self.yanked.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Counts a visit to another world, and says whether it's time to stop.
+    fn tug(&self) -> bool {
+        if self.yanked() {
+            return true;
+        }
+        let reading = self.odometer.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+        if let Some(budget) = self.node_budget {
+            if reading > budget {
+                self.yank();
+                return true;
+            }
+        }
+        if reading % LEASH_CLOCK_INTERVAL == 0 {
+            if let Some(deadline) = *self.deadline.lock() {
+                if time::get_time() > deadline {
+                    self.yank();
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+
+/// When iterative deepening should settle for the deepest forecasts it has
+/// so far (absent all of these, it never will).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Curfew {
+    pub timeout: Option<time::Duration>,
+    /// worlds to visit, in all
+    pub nodes: Option<usize>,
+    /// Stop upon finding a mate in this many movements or fewer, or after
+    /// searching deep enough to be sure there isn't one.
+    pub mate_in: Option<u8>,
+    /// budget time for a movement in a game on the clock
+    pub hourglass: Option<Hourglass>,
+    /// the deepest to go
+    pub depth: Option<u8>,
+}
+
+
+/// Plies (or fractions thereof) by which to search a movement more deeply
+/// than its siblings, so that the horizon doesn't fall in the middle of a
+/// forcing sequence. Fractions accumulate along a line of play until they
+/// amount to a whole ply.
+#[derive(Debug, Clone, Copy)]
+pub struct Extensions {
+    /// for movements that critically endanger the opposing figurehead
+    pub endangerment: f32,
+    /// for the only legal movement out of critical endangerment
+    pub single_reply: f32,
+    /// for stunning back on the locale where the opposition just stunned
+    pub recapture: f32,
+    /// for servants arriving on the rank before their ascension
+    pub seventh_heaven: f32,
+    /// the most that any one line of play may be extended in all
+    pub budget: f32,
+}
+
+impl Default for Extensions {
+    fn default() -> Self {
+        Self {
+            endangerment: 1.0,
+            single_reply: 1.0,
+            recapture: 0.5,
+            seventh_heaven: 0.5,
+            budget: 4.0,
+        }
+    }
+}
+
+impl Extensions {
+    /// How much to extend `premonition`, made in reply to `predecessor`,
+    /// given that `extended` plies of the budget are already spent on this
+    /// line. No one movement gets more than a ply.
+    fn assess(&self, premonition: &Commit, predecessor: Option<Commit>,
+              single_reply: bool, extended: f32) -> f32 {
+        let mut extension = 0.0;
+        if premonition.tree.in_critical_endangerment(premonition.tree.initiative) {
+            extension += self.endangerment;
+        }
+        if single_reply {
+            extension += self.single_reply;
+        }
+        if let Some(provocation) = predecessor {
+            if provocation.hospitalization.is_some() &&
+                premonition.hospitalization.is_some() &&
+                premonition.patch.whither == provocation.patch.whither {
+                extension += self.recapture;
+            }
+        }
+        if premonition.patch.star.job_description == JobDescription::Servant {
+            let seventh_heaven = match premonition.patch.star.team {
+                Team::Orange => Pinfield(HIGH_SEVENTH_HEAVEN),
+                Team::Blue => Pinfield(LOW_SEVENTH_HEAVEN),
+            };
+            if seventh_heaven.query(premonition.patch.whither) {
+                extension += self.seventh_heaven;
+            }
+        }
+        extension.min(1.0).min(self.budget - extended).max(0.0)
+    }
+}
+
+
+/// How far (per ply of remaining depth) the static score must be
+/// from the α–β window before we trust it over searching, within
+/// `maximum_depth` plies of the leaves.
+#[derive(Debug, Clone, Copy)]
+pub struct Margins {
+    /// Reverse futility: a static score this far above β will surely hold
+    /// up for the few plies left.
+    pub reverse_futility: Score,
+    /// Futility: quiet movements from a static score this far below α
+    /// won't make up the difference.
+    pub futility: Score,
+    /// Razoring: from a static score this far below α, only stunnings could
+    /// possibly help, so quiescence has the final word.
+    pub razoring: Score,
+    pub maximum_depth: i8,
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Self {
+            reverse_futility: Score(120),
+            futility: Score(150),
+            razoring: Score(300),
+            maximum_depth: 3,
+        }
+    }
+}
+
+
+pub fn orientation(team: Team) -> i32 {
     match team {
-        Team::Orange => 1.0,
-        Team::Blue => -1.0,
+        Team::Orange => 1,
+        Team::Blue => -1,
     }
 }
 
-pub fn figurine_valuation(agent: Agent) -> f32 {
+/// The conventional value of a figurine, oriented by team. (The figurehead
+/// is priceless—the game is over when he's in critical endangerment—but
+/// gets a large finite value for the purpose of weighing stunnings.)
+pub fn figurine_valuation(agent: Agent) -> Score {
     let value = match agent.job_description {
         // en.wikipedia.org/wiki/
         // Chess_piece_relative_value#Hans_Berliner.27s_system
-        JobDescription::Servant => 1.0,
-        JobDescription::Pony => 3.2,
-        JobDescription::Scholar => 3.3,
-        JobDescription::Cop => 5.1,
-        JobDescription::Princess => 8.8,
-        JobDescription::Figurehead => 20000.0,
+        JobDescription::Servant => Score(100),
+        JobDescription::Pony => Score(320),
+        JobDescription::Scholar => Score(330),
+        JobDescription::Cop => Score(510),
+        JobDescription::Princess => Score(880),
+        // (short of the mate scores, which it mustn't be confused with)
+        JobDescription::Figurehead => Score(20000),
+    };
+    value * orientation(agent.team)
+}
+
+#[cfg(test)]
+pub fn score(world: WorldState) -> Score {
+    assess(world, judgment::servant_skeleton(world.orange_servants,
+                                             world.blue_servants),
+           &EvalParams::default())
+}
+
+/// the servant-structure table, which the search threads share
+pub type ServantBank = Arc<parking_lot::Mutex<LruCache<(Pinfield, Pinfield),
+                                                       ServantStructure,
+                                                       BuildHasherDefault<XxHash>>>>;
+
+/// Score the world as the search does, looking up the skeleton of its
+/// servants in the servant-structure table (and remembering it there if it
+/// wasn't already). If another thread has the table, we work the skeleton
+/// out for ourselves rather than wait our turn.
+fn recollect_and_score(
+    world: WorldState, eval: &EvalParams, servant_bank: &ServantBank,
+    stats: &mut SearchStats) -> Score {
+    let bones = (world.orange_servants, world.blue_servants);
+    let skeleton = match servant_bank.try_lock() {
+        Some(mut bank) => {
+            stats.servant_probes += 1;
+            let remembered = bank.get_mut(&bones).cloned();
+            match remembered {
+                Some(skeleton) => {
+                    stats.servant_hits += 1;
+                    skeleton
+                }
+                None => {
+                    let skeleton =
+                        judgment::servant_skeleton(bones.0, bones.1);
+                    bank.insert(bones, skeleton);
+                    skeleton
+                }
+            }
+        }
+        None => judgment::servant_skeleton(bones.0, bones.1),
     };
-    orientation(agent.team) * value
-}
-
-pub fn score(world: WorldState) -> f32 {
-    let mut valuation = 0.0;
-
-    valuation += REWARD_FOR_INITIATIVE * orientation(world.initiative);
-
-    for team in Team::league() {
-        for agent in &Agent::dramatis_personæ(team) {
-            valuation += f32::from(world.agent_to_pinfield_ref(*agent)
-                                   .pincount()) *
-                figurine_valuation(*agent);
-        }
-        // breadth of scholarship bonus
-        if world.agent_to_pinfield_ref(Agent {
-                    team,
-                    job_description: JobDescription::Scholar,
-                }).pincount() >= 2 {
-            valuation += orientation(team) * 0.5
-        }
-    }
-
-    // ponies and servants want to be in the center of the world's action
-    let center = Pinfield(CENTER_OF_THE_WORLD);
-    // cast to signed to avoid overflow
-    let orange_centerism: i8 = world.orange_servants
-                                    .union(world.orange_ponies)
-                                    .intersection(center)
-                                    .pincount() as i8;
-    let blue_centerism: i8 = world.blue_servants
-                                  .union(world.blue_ponies)
-                                  .intersection(center)
-                                  .pincount() as i8;
-    valuation += 0.1 * f32::from(orange_centerism - blue_centerism);
-
-    // a cop's favorite beat is the seventh rank
-    let high_seventh = Pinfield(HIGH_SEVENTH_HEAVEN);
-    let orange_beat = world.orange_cops.intersection(high_seventh).pincount();
-    valuation += 0.5 * f32::from(orange_beat);
-    let low_seventh = Pinfield(LOW_SEVENTH_HEAVEN);
-    let blue_beat = world.blue_cops.intersection(low_seventh).pincount();
-    valuation -= 0.5 * f32::from(blue_beat);
-
-    // servants who walk behind other servants to hide must be punished
-    for raw_file in &FILES {
-        let file = Pinfield(*raw_file);
-        let orange_servants_in_line = world.orange_servants
-                                           .intersection(file)
-                                           .pincount();
-        // Putting a precise number on how bad extra servants on a file are
-        // seems to be quite hard, and a smarter engine might choose more
-        // dynamically, but half-a-point is OK, I think.
-        // Wikipedia has examples where a doubled servant is worth anywhere
-        // from .3 to .75 points.
-        if orange_servants_in_line > 1 {
-            valuation -= 0.5 * f32::from(orange_servants_in_line - 1);
-        }
-        let blue_servants_in_line = world.blue_servants
-                                           .intersection(file)
-                                           .pincount();
-        if blue_servants_in_line > 1 {
-            valuation += 0.5 * f32::from(blue_servants_in_line - 1);
-        }
-    }
-
-    // servants should aspire to something more in life someday
-    let orange_subascendants = world.orange_servants
-                                    .intersection(high_seventh)
-                                    .pincount();
-    valuation += 1.8 * f32::from(orange_subascendants);
-    let high_colonelcy = Pinfield(HIGH_COLONELCY);
-    let orange_subsubascendants = world.orange_servants
-                                       .intersection(high_colonelcy)
-                                       .pincount();
-    valuation += 0.6 * f32::from(orange_subsubascendants);
-    let blue_subascendants = world.blue_servants
-                                  .intersection(low_seventh)
-                                  .pincount();
-    valuation -= 1.8 * f32::from(blue_subascendants);
-    let low_colonelcy = Pinfield(LOW_COLONELCY);
-    let blue_subsubascendants = world.blue_servants
-                                     .intersection(low_colonelcy)
-                                     .pincount();
-    valuation -= 0.6 * f32::from(blue_subsubascendants);
-
-    // secret service eligbility has option value
-    if world.orange_west_service_eligibility() ||
-       world.orange_east_service_eligibility() {
-        valuation += 0.1
-    }
-    if world.blue_west_service_eligibility() || world.blue_east_service_eligibility() {
-        valuation -= 0.1
+    assess(world, skeleton, eval)
+}
+
+/// how many of a team's figurines of the given job description stand in the
+/// given region
+fn presence(world: &WorldState, team: Team, job_description: JobDescription,
+            region: u64) -> i32 {
+    i32::from(world.agent_to_pinfield_ref(Agent::new(team, job_description))
+              .intersection(Pinfield(region)).pincount())
+}
+
+fn seventh_heaven(team: Team) -> u64 {
+    match team {
+        Team::Orange => HIGH_SEVENTH_HEAVEN,
+        Team::Blue => LOW_SEVENTH_HEAVEN,
+    }
+}
+
+fn colonelcy(team: Team) -> u64 {
+    match team {
+        Team::Orange => HIGH_COLONELCY,
+        Team::Blue => LOW_COLONELCY,
+    }
+}
+
+fn initiative(world: &WorldState, team: Team, eval: &EvalParams) -> Score {
+    if world.initiative == team { eval.initiative } else { Score(0) }
+}
+
+fn material(world: &WorldState, team: Team, eval: &EvalParams) -> Score {
+    // (the figurehead is worth nothing here, the search dealing with his
+    // fate separately)
+    Agent::dramatis_personæ(team).iter()
+        .map(|agent| eval.valuation(agent.job_description) *
+             i32::from(world.census().headcount(*agent)))
+        .fold(Score(0), |total, value| total + value)
+}
+
+/// breadth of scholarship bonus
+fn scholar_pair(world: &WorldState, team: Team, eval: &EvalParams) -> Score {
+    if world.census().headcount(Agent::new(team, JobDescription::Scholar)) >= 2 {
+        eval.scholar_pair
+    } else {
+        Score(0)
     }
+}
+
+/// ponies and servants want to be in the center of the world's action
+fn centrality(world: &WorldState, team: Team, eval: &EvalParams) -> Score {
+    eval.centrality *
+        (presence(world, team, JobDescription::Servant, CENTER_OF_THE_WORLD) +
+         presence(world, team, JobDescription::Pony, CENTER_OF_THE_WORLD))
+}
+
+/// a cop's favorite beat is the seventh rank
+fn cop_beat(world: &WorldState, team: Team, eval: &EvalParams) -> Score {
+    eval.cop_beat *
+        presence(world, team, JobDescription::Cop, seventh_heaven(team))
+}
+
+/// servants who walk behind other servants to hide must be punished
+fn doubled_servants(world: &WorldState, team: Team, eval: &EvalParams)
+                    -> Score {
+    -eval.doubled_servant * FILES.iter()
+        .map(|&file| (presence(world, team, JobDescription::Servant, file) - 1)
+             .max(0))
+        .sum::<i32>()
+}
+
+/// servants should aspire to something more in life someday
+fn ascension_bands(world: &WorldState, team: Team, eval: &EvalParams)
+                   -> Score {
+    eval.subascension *
+        presence(world, team, JobDescription::Servant, seventh_heaven(team)) +
+    eval.subsubascension *
+        presence(world, team, JobDescription::Servant, colonelcy(team))
+}
+
+/// secret service eligbility has option value
+fn service_eligibility(world: &WorldState, team: Team, eval: &EvalParams)
+                       -> Score {
+    let eligible = match team {
+        Team::Orange => world.orange_west_service_eligibility() ||
+            world.orange_east_service_eligibility(),
+        Team::Blue => world.blue_west_service_eligibility() ||
+            world.blue_east_service_eligibility(),
+    };
+    if eligible { eval.service_eligibility } else { Score(0) }
+}
+
+/// The considerations that count the same in the middlegame as in the
+/// endgame, each appraising what it's worth to a team. Both `assess` and
+/// `score_breakdown` go by this list, so whatever is added to it gets
+/// explained as well as counted.
+static EVEN_CONSIDERATIONS: [(&'static str,
+                              fn(&WorldState, Team, &EvalParams) -> Score); 8] = [
+    ("initiative", initiative),
+    ("material", material),
+    ("scholar pair", scholar_pair),
+    ("centrality", centrality),
+    ("cop beat", cop_beat),
+    ("doubled servants", doubled_servants),
+    ("ascension bands", ascension_bands),
+    ("service eligibility", service_eligibility),
+];
+
+/// Score the world (from Orange's perspective) with the given weights, given
+/// the skeleton of its servants.
+pub fn assess(world: WorldState, skeleton: ServantStructure,
+              eval: &EvalParams) -> Score {
+    let mut valuation = Score(0);
+
+    for &(_, consideration) in &EVEN_CONSIDERATIONS {
+        valuation += consideration(&world, Team::Orange, eval) -
+            consideration(&world, Team::Blue, eval);
+    }
+
+    // everyone has their favorite haunts, which change as the world empties
+    let phase = terrain::phase(&world);
+    let (middlegame, endgame) = terrain::appraise(&world);
+    valuation += terrain::taper(middlegame, endgame, phase);
+
+    // an officer with nowhere to go is hardly better than no officer at all
+    let (middlegame, endgame) = judgment::mobility(&world);
+    valuation += terrain::taper(middlegame, endgame, phase);
+
+    // servants are the soul of the game
+    let (middlegame, endgame) = judgment::servant_structure(&world, skeleton);
+    valuation += terrain::taper(middlegame, endgame, phase);
+
+    // the figurehead needs shelter while there are officers to fear
+    valuation += terrain::taper(judgment::figurehead_safety(&world), Score(0),
+                                phase);
 
     valuation
 }
 
-fn mvv_lva_heuristic(commit: &Commit) -> f32 {
+/// One of the considerations that go into a score: what it's worth to
+/// Orange and to Blue (each from their own perspective), and on balance
+/// (from Orange's).
+pub type Consideration = (&'static str, Score, Score, Score);
+
+fn each_team<T, F: Fn(Team) -> T>(appraisal: F) -> (T, T) {
+    (appraisal(Team::Orange), appraisal(Team::Blue))
+}
+
+/// The considerations that go into the score of a world (whose balances add
+/// up to it), for when we want to know what evaluation was thinking. (The
+/// tapered considerations are tapered after balancing, so a team's share of
+/// them might be off by a rounding error.)
+pub fn score_breakdown(world: WorldState, eval: &EvalParams)
+                       -> Vec<Consideration> {
+    let phase = terrain::phase(&world);
+    let tapered = |term, (orange, blue): ((Score, Score), (Score, Score))| {
+        (term,
+         terrain::taper(orange.0, orange.1, phase),
+         terrain::taper(blue.0, blue.1, phase),
+         terrain::taper(orange.0 - blue.0, orange.1 - blue.1, phase))
+    };
+
+    let mut breakdown = EVEN_CONSIDERATIONS.iter()
+        .map(|&(term, consideration)| {
+            let (orange, blue) =
+                each_team(|team| consideration(&world, team, eval));
+            (term, orange, blue, orange - blue)
+        })
+        .collect::<Vec<Consideration>>();
+    breakdown.extend(vec![
+        tapered("terrain", each_team(|team| terrain::standing(&world, team))),
+        tapered("mobility", each_team(|team| judgment::freedom(&world, team))),
+        tapered("servant structure",
+                each_team(|team| judgment::servant_formation(&world, team))),
+        tapered("figurehead safety", each_team(|team| {
+            (judgment::shelter(&world, team), Score(0))
+        })),
+    ]);
+    breakdown
+}
+
+fn mvv_lva_heuristic(commit: &Commit) -> Score {
     // https://chessprogramming.wikispaces.com/MVV-LVA
     match commit.hospitalization {
         Some(patient) => {
             (figurine_valuation(patient) - figurine_valuation(commit.patch.star))
         }
-        None => 0.0,
+        None => Score(0),
+    }
+}
+
+// how many plies' worth of killer movements to keep track of
+const KILLER_PLY_CAPACITY: usize = 64;
+
+
+/// what the search has learned about which movements tend to be good,
+/// independently of the particular world-states in which they were made
+#[derive(Debug, Clone)]
+pub struct Intuition {
+    /// how often (weighted by depth) each patch has caused a cutoff
+    pub history: fnv::FnvHashMap<Patch, u32>,
+    /// the two most recent quiet movements to cause a cutoff at each ply
+    pub killers: Vec<[Option<Patch>; 2]>,
+    /// the quiet movement that most recently refuted each opposing movement
+    pub countermoves: fnv::FnvHashMap<Patch, Patch>,
+}
+
+impl Default for Intuition {
+    fn default() -> Self {
+        Self {
+            history: fnv::FnvHashMap::default(),
+            killers: vec![[None, None]; KILLER_PLY_CAPACITY],
+            countermoves: fnv::FnvHashMap::default(),
+        }
+    }
+}
+
+impl Intuition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn killers_at(&self, ply: u8) -> [Option<Patch>; 2] {
+        match self.killers.get(ply as usize) {
+            Some(&killers) => killers,
+            None => [None, None],
+        }
+    }
+
+    fn remember_cutoff(&mut self, commit: &Commit, depth: i8, ply: u8,
+                       predecessor: Option<Patch>) {
+        let intuition = self.history.entry(commit.patch).or_insert(0);
+        *intuition += 2u32.pow(depth as u32);
+        if commit.hospitalization.is_some() || commit.ascension.is_some() {
+            // stunnings and ascensions get sorted to the front on their own
+            return;
+        }
+        if let Some(killers) = self.killers.get_mut(ply as usize) {
+            if killers[0] != Some(commit.patch) {
+                killers[1] = killers[0];
+                killers[0] = Some(commit.patch);
+            }
+        }
+        if let Some(provocation) = predecessor {
+            self.countermoves.insert(provocation, commit.patch);
+        }
+    }
+
+    /// Fade the history between iterations, so that what was learned at
+    /// shallow depths doesn't drown out what we learn deeper.
+    pub fn age(&mut self) {
+        for intuition in self.history.values_mut() {
+            *intuition /= 2;
+        }
+        self.history.retain(|_, intuition| *intuition > 0);
+    }
+
+    /// Take in the history one thread learned on its own, counting only
+    /// what it added to the intuition it started out with.
+    fn absorb(&mut self, learned: &Intuition, inherited: &Intuition) {
+        for (patch, &intuition) in &learned.history {
+            let before = inherited.history.get(patch).cloned().unwrap_or(0);
+            if intuition > before {
+                *self.history.entry(*patch).or_insert(0) += intuition - before;
+            }
+        }
     }
 }
 
+
+/// The net material gain (from the perspective of the team making the patch)
+/// of the sequence of stunnings that it sets off at its destination,
+/// assuming that each team always stuns with its least valuable figurine
+/// and stops whenever continuing would be a loss. (Ascensions are ignored.)
+pub fn static_exchange(world: &WorldState, patch: Patch) -> Score {
+    let battleground = patch.whither;
+    let opposition = patch.star.team.opposition();
+    let mut occupied = world.occupied().quench(patch.whence);
+    let first_victim = match world.occupying_affiliated_agent(battleground,
+                                                              opposition) {
+        Some(victim) => figurine_valuation(victim).abs(),
+        None => {
+            if patch.star.job_description == JobDescription::Servant &&
+                patch.whence.file() != patch.whither.file() {
+                // passing by
+                occupied = occupied.quench(
+                    Locale::new(patch.whence.rank(), patch.whither.file()));
+                figurine_valuation(patch.star).abs()
+            } else {
+                Score(0)
+            }
+        }
+    };
+
+    // `gains[i]` is what the team making the `i`th stunning would come away
+    // with if the other team always took back whatever they could
+    let mut gains = Vec::with_capacity(32);
+    gains.push(first_victim);
+    let mut standing_on_battleground = figurine_valuation(patch.star).abs();
+    let mut side = opposition;
+    loop {
+        let attackers = world.attackers_of(battleground, occupied);
+        let next_stunner = Agent::dramatis_personæ(side)
+            .iter()
+            .filter_map(|&agent| {
+                let candidates = world.agent_to_pinfield_ref(agent)
+                    .intersection(attackers);
+                if candidates.pincount() > 0 {
+                    Some((agent, candidates.to_locales()[0]))
+                } else {
+                    None
+                }
+            })
+            .next();
+        let (stunner, whence) = match next_stunner {
+            Some(stunning) => stunning,
+            None => { break; }
+        };
+        let previous_gain = gains[gains.len() - 1];
+        gains.push(standing_on_battleground - previous_gain);
+        if (-previous_gain).max(standing_on_battleground - previous_gain) <
+                Score(0) {
+            // neither stunning nor standing pat can help whoever's turn it
+            // is, so it doesn't matter what happens afterwards
+            break;
+        }
+        standing_on_battleground = figurine_valuation(stunner).abs();
+        occupied = occupied.quench(whence);
+        side = side.opposition();
+    }
+    while gains.len() > 1 {
+        let last = gains.pop().unwrap();
+        let previous = gains.len() - 1;
+        gains[previous] = -(-gains[previous]).max(last);
+    }
+    gains[0]
+}
+
+/// A movement as its own team sees it (as if they were Orange), by which to
+/// break ties when ordering movements, so that a world and its mirror image
+/// get searched alike.
+fn perspective(commit: &Commit) -> (u32, u32, Option<u8>) {
+    let (whence, whither) = match commit.patch.star.team {
+        Team::Orange => (commit.patch.whence, commit.patch.whither),
+        Team::Blue => (commit.patch.whence.mirrored(),
+                       commit.patch.whither.mirrored()),
+    };
+    (whence.pindex(), whither.pindex(),
+     commit.ascension.map(|agent| agent.job_description as u8))
+}
+
 fn order_movements_intuitively(
-        experience: &fnv::FnvHashMap<Patch, u32>,
-        commits: &mut Vec<Commit>) -> Vec<Commit> {
-    let mut sorted: Vec<(Commit, Option<&u32>, f32)> = Vec::with_capacity(commits.len());
+        world: &WorldState, experience: &Intuition, commits: &mut Vec<Commit>,
+        ply: u8, predecessor: Option<Patch>, hunch: Option<Patch>)
+        -> Vec<Commit> {
+    let killers = experience.killers_at(ply);
+    let countermove = predecessor.and_then(|p| experience.countermoves.get(&p));
+    let mut sorted: Vec<(Commit, u8, Score, Score, Option<&u32>)> =
+        Vec::with_capacity(commits.len());
     for c in commits {
-        sorted.push((*c, experience.get(&c.patch), mvv_lva_heuristic(&c)));
+        // the hunch first, then stunnings that don't lose material and
+        // ascensions, then killers, then the countermove, then quiet
+        // movements, and finally stunnings that do lose material
+        let exchange = if c.hospitalization.is_some() {
+            static_exchange(world, c.patch)
+        } else {
+            Score(0)
+        };
+        let precedence = if hunch == Some(c.patch) {
+            5
+        } else if c.hospitalization.is_some() {
+            if exchange >= Score(0) { 4 } else { 0 }
+        } else if c.ascension.is_some() {
+            4
+        } else if killers.contains(&Some(c.patch)) {
+            3
+        } else if countermove == Some(&c.patch) {
+            2
+        } else {
+            1
+        };
+        sorted.push((*c, precedence, exchange, mvv_lva_heuristic(&c),
+                     experience.history.get(&c.patch)));
     }
     sorted.sort_unstable_by(|a, b| {
-        match b.1.cmp(&a.1) {
-            Ordering::Equal => b.2.partial_cmp(&a.2).unwrap_or(Ordering::Equal),
-            other => other,
-        }
+        b.1.cmp(&a.1)
+            .then(b.2.cmp(&a.2))
+            .then(b.3.cmp(&a.3))
+            .then(b.4.cmp(&a.4))
+            .then(perspective(&a.0).cmp(&perspective(&b.0)))
     });
     sorted.iter().map(|c| { c.0 }).collect()
 }
@@ -183,7 +846,6 @@ pub trait Memory: Clone + Send {
     fn recombine(&mut self, other: Self);
     fn flash(patch: Patch) -> Self;
     fn blank() -> Self;
-    fn readable(&self) -> String;
 }
 
 impl Memory for Patch {
@@ -206,10 +868,6 @@ impl Memory for Patch {
         }
     }
 
-    fn readable(&self) -> String {
-        self.abbreviated_pagan_movement_rune()
-    }
-
 }
 
 impl Memory for Variation {
@@ -223,33 +881,139 @@ impl Memory for Variation {
     fn blank() -> Self {
         vec![]
     }
+}
+
+/// What a remembered score says about the true value of a world: a search
+/// whose score fell outside its α–β window stopped looking once it knew as
+/// much, so the truth might lie further out still.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// the world is at least this good (the search failed high)
+    Lower,
+    /// the world is at most this good (the search failed low)
+    Upper,
+}
+
+/// What the déjà vu table remembers about a world: its score (and what
+/// kind of bound it is), how much extension had already been spent on the
+/// line leading to it, and the movement that earned it (if any). Whole
+/// lines of play are left to `Foresight`, so that every entry is the same
+/// (small) size.
+#[derive(Debug, Clone, Copy)]
+pub struct Lodestar {
+    pub score: Score,
+    pub bound: Bound,
+    pub extended: f32,
+    pub best: Option<Patch>,
+}
+
+impl Lodestar {
+    fn new(score: Score, bound: Bound, extended: f32, best: Option<Patch>)
+           -> Self {
+        Self { score, bound, extended, best }
+    }
 
-    fn readable(&self) -> String {
-        pagan_variation_format(&self)
+    /// Whether the remembered (unmoored) `score` answers for a search with
+    /// the given window, that has already spent `extended` plies of
+    /// extension—not if the remembered search had less extension left to
+    /// spend than we do.
+    fn suffices(&self, score: Score, α: Score, β: Score, extended: f32)
+                -> bool {
+        self.extended <= extended && match self.bound {
+            Bound::Exact => true,
+            Bound::Lower => score >= β,
+            Bound::Upper => score <= α,
+        }
     }
 }
 
-#[derive(Clone)]
-pub struct Lodestar<T: Memory> {
-    pub score: f32,
-    pub memory: T,
+
+/// The principal variations of a search in progress, kept in a triangular
+/// table: the line from ply `p` (at most `FORESIGHT_HORIZON - p` movements
+/// long) is the best movement found there so far, followed by the line
+/// from ply `p + 1`. Each search thread keeps its own.
+pub struct Foresight {
+    table: Vec<Patch>,
+    lengths: Vec<usize>,
 }
 
-impl<T: Memory> Lodestar<T> {
-    fn new(score: f32, memory: T) -> Self {
+impl Foresight {
+    pub fn new() -> Self {
         Self {
-            score,
-            memory,
+            table: vec![Patch::blank();
+                        FORESIGHT_HORIZON * (FORESIGHT_HORIZON + 1) / 2],
+            lengths: vec![0; FORESIGHT_HORIZON],
+        }
+    }
+
+    /// where the line from `ply` starts in the table
+    fn row(ply: usize) -> usize {
+        ply * FORESIGHT_HORIZON - ply * ply.saturating_sub(1) / 2
+    }
+
+    fn forget(&mut self, ply: u8) {
+        if let Some(length) = self.lengths.get_mut(usize::from(ply)) {
+            *length = 0;
         }
     }
+
+    /// `patch` is the best movement at `ply` so far; the rest of its line
+    /// is whatever was just found at the next ply.
+    fn adopt(&mut self, ply: u8, patch: Patch) {
+        let ply = usize::from(ply);
+        if ply >= FORESIGHT_HORIZON {
+            return;
+        }
+        let here = Self::row(ply);
+        self.table[here] = patch;
+        let mut length = 1;
+        if ply + 1 < FORESIGHT_HORIZON {
+            let there = Self::row(ply + 1);
+            for i in 0..self.lengths[ply + 1] {
+                self.table[here + 1 + i] = self.table[there + i];
+            }
+            length += self.lengths[ply + 1];
+        }
+        self.lengths[ply] = length;
+    }
+
+    /// Stand in for a search at `ply` that we didn't need to do, because
+    /// the déjà vu table remembered its best movement.
+    fn recall(&mut self, ply: u8, best: Option<Patch>) {
+        self.forget(ply);
+        if let Some(patch) = best {
+            if usize::from(ply) < FORESIGHT_HORIZON {
+                let here = Self::row(usize::from(ply));
+                self.table[here] = patch;
+                self.lengths[usize::from(ply)] = 1;
+            }
+        }
+    }
+
+    pub fn line(&self, ply: u8) -> &[Patch] {
+        let ply = usize::from(ply);
+        if ply >= FORESIGHT_HORIZON {
+            return &[];
+        }
+        let here = Self::row(ply);
+        &self.table[here..here + self.lengths[ply]]
+    }
+
+    /// the `opening` movement followed by the line found after it, in
+    /// whatever form the caller wants to remember it
+    fn envision<T: Memory>(&self, opening: Patch) -> T {
+        let mut memory = T::flash(opening);
+        for &patch in self.line(1) {
+            memory.recombine(T::flash(patch));
+        }
+        memory
+    }
 }
 
-impl<T: Memory> fmt::Debug for Lodestar<T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(f,
-               "Lodestar {{ score: {}, memory: {} }}",
-               self.score,
-               self.memory.readable())
+impl Default for Foresight {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -267,39 +1031,259 @@ impl SpaceTime {
 }
 
 
+fn late_move_pruning_threshold(depth: i8) -> usize {
+    3 + (depth * depth) as usize
+}
+
+
+/// Search only the movements that could upset the score at a leaf—
+/// stunnings and ascensions, or everything if our figurehead is critically
+/// endangered—so that we don't score a world in the middle of an exchange.
+/// Unless endangered, we can also "stand pat" by declining to make any of
+/// them.
 #[allow(too_many_arguments)]
-pub fn α_β_negamax_search<T: Memory>(
-    world: WorldState, depth: i8, mut α: f32, β: f32,
-    memory_bank: Arc<parking_lot::Mutex<LruCache<SpaceTime, Lodestar<T>,
-                                    BuildHasherDefault<XxHash>>>>,
-    intuition_bank: Arc<parking_lot::Mutex<fnv::FnvHashMap<Patch, u32>>>,
-    quiet: Option<u8>)
-        -> Lodestar<T> {
+pub fn quiescence_search(
+    world: WorldState, mut α: Score, β: Score, ply: u8, checks: bool,
+    eval: &EvalParams, servant_bank: ServantBank,
+    leash: &Leash, stats: &mut SearchStats, foresight: &mut Foresight)
+        -> Score {
+    stats.quiesce(ply);
+    foresight.forget(ply);
+    if leash.tug() {
+        return Score(0);
+    }
+    if world.in_critical_endangerment(world.initiative.opposition()) {
+        return mating(ply);
+    }
+    let standing_pat = orientation(world.initiative) *
+        recollect_and_score(world, eval, &servant_bank, stats);
+    let endangered = world.in_critical_endangerment(world.initiative);
+    let mut optimum = NEG_INFINITY;
+    if !endangered {
+        if standing_pat >= β {
+            return standing_pat;
+        }
+        if standing_pat > α {
+            α = standing_pat;
+        }
+        optimum = standing_pat;
+    }
 
     let mut premonitions = world.reckless_lookahead();
-    let mut optimum = NEG_INFINITY;
-    let mut optimand = T::blank();
-    if depth <= 0 || premonitions.is_empty() {
-        let potential_score = orientation(world.initiative) * score(world);
-        match quiet {
-            None => {
-                return Lodestar::new(potential_score, T::blank());
-            },
-            Some(extension) => {
-                if depth.abs() >= extension as i8 {
-                    return Lodestar::new(potential_score, T::blank());
+    if endangered {
+        premonitions.retain(
+            |c| !c.tree.in_critical_endangerment(world.initiative));
+        if premonitions.is_empty() {
+            return -mating(ply);
+        }
+    } else {
+        premonitions.retain(|c| {
+            if let Some(ascended) = c.ascension {
+                // other ascensions are rarely better than princesshood, and
+                // can wait for the main search
+                return ascended.job_description == JobDescription::Princess;
+            }
+            match c.hospitalization {
+                Some(patient) => {
+                    // delta pruning: hopeless even if the stunning were free
+                    standing_pat + figurine_valuation(patient).abs() +
+                        DELTA_MARGIN >= α &&
+                        static_exchange(&world, c.patch) >= Score(0)
                 }
-                premonitions = premonitions.into_iter()
-                    .filter(|c| c.hospitalization.is_some())
-                    .collect::<Vec<_>>();
-                if premonitions.is_empty() {
-                    return Lodestar::new(potential_score, T::blank())
-                } else {
-                    optimum = potential_score;
+                None => {
+                    checks && c.tree.in_critical_endangerment(c.tree.initiative)
                 }
             }
+        });
+    }
+    premonitions.sort_unstable_by(|a, b| {
+        mvv_lva_heuristic(b).cmp(&mvv_lva_heuristic(a))
+            .then(perspective(a).cmp(&perspective(b)))
+    });
+
+    for premonition in premonitions {
+        let value = -quiescence_search(  // nega-
+            premonition.tree, -β, -α, ply.saturating_add(1), false, eval,
+            servant_bank.clone(), leash, stats, foresight);
+        if value > optimum {
+            optimum = value;
+            foresight.adopt(ply, premonition.patch);
+        }
+        if value > α {
+            α = value;
         }
+        if α >= β {
+            break;
+        }
+    }
+    optimum
+}
+
+
+/// Consults the déjà vu table for the value of a premonition (from the
+/// perspective of the team making it) at the given depth, searching and
+/// remembering it if we haven't seen it before (or if what we remember
+/// doesn't settle the question for this window). Either way, the line that
+/// follows it is left in `foresight` at the next ply.
+#[allow(too_many_arguments)]
+fn recall_or_search(
+    premonition: Commit, depth: i8, ply: u8, α: Score, β: Score,
+    memory_bank: Arc<parking_lot::Mutex<LruCache<SpaceTime, Lodestar,
+                                    BuildHasherDefault<XxHash>>>>,
+    servant_bank: ServantBank,
+    intuition_bank: Arc<parking_lot::Mutex<Intuition>>, leash: &Leash,
+    params: SearchParams, extended: f32, stats: &mut SearchStats,
+    foresight: &mut Foresight) -> Score {
+    let space_time = SpaceTime::new(premonition.tree, depth);
+    let mut hunch = None;
+    {
+        let mut open_vault = memory_bank.lock();
+        stats.memory_probes += 1;
+        if let Some(remembered_lodestar) = open_vault.get_mut(&space_time) {
+            let score = remembered_lodestar.score.unmoored(ply);
+            if remembered_lodestar.suffices(score, α, β, extended) {
+                stats.memory_hits += 1;
+                foresight.recall(ply + 1, remembered_lodestar.best);
+                return score;
+            }
+            // even if the score won't do, the movement that earned it is
+            // still the one to try first
+            hunch = remembered_lodestar.best;
+        }
+    }
+    let value = -α_β_negamax_search(  // nega-
+        premonition.tree, depth - 1, ply + 1,
+        -β, -α,
+        memory_bank.clone(), servant_bank, intuition_bank, leash,
+        params, Some(premonition), hunch, extended, stats, foresight
+    );
+    let bound = if value <= α {
+        Bound::Upper
+    } else if value >= β {
+        Bound::Lower
+    } else {
+        Bound::Exact
     };
+    let memorandum = Lodestar::new(value.moored(ply), bound, extended,
+                                   foresight.line(ply + 1).first().cloned());
+    stats.memory_stores += 1;
+    if memory_bank.lock().insert(space_time, memorandum).is_some() {
+        // another thread got here first
+        stats.memory_overwrites += 1;
+    }
+    value
+}
+
+
+/// Search the world to the given depth, `ply` movements after the search
+/// started, where the opposition got here by making `predecessor` (or by
+/// passing, if it's `None`), and `extended` plies of extension have already
+/// been spent on the line. The `hunch` (if any) is tried first, the déjà vu
+/// table having remembered it as the best movement from a shallower or
+/// narrower look at this world. What we find along the way gets tallied in
+/// `stats`, and the best line of play from here is left in `foresight`.
+#[allow(too_many_arguments)]
+pub fn α_β_negamax_search(
+    world: WorldState, depth: i8, ply: u8, mut α: Score, β: Score,
+    memory_bank: Arc<parking_lot::Mutex<LruCache<SpaceTime, Lodestar,
+                                    BuildHasherDefault<XxHash>>>>,
+    servant_bank: ServantBank,
+    intuition_bank: Arc<parking_lot::Mutex<Intuition>>, leash: &Leash,
+    params: SearchParams, predecessor: Option<Commit>, hunch: Option<Patch>,
+    extended: f32, stats: &mut SearchStats, foresight: &mut Foresight)
+        -> Score {
+    foresight.forget(ply);
+    if depth > 0 || !params.quiescence {
+        // (otherwise, quiescence will count it)
+        stats.visit(ply);
+        if leash.tug() {
+            return Score(0);
+        }
+    }
+    let provocation = predecessor.map(|c| c.patch);
+
+    if world.in_critical_endangerment(world.initiative.opposition()) {
+        // the opposition left their figurehead for the taking, which is as
+        // good as it gets (the movement that got them here, being illegal,
+        // won't be chosen, but we need to say so quickly)
+        return mating(ply);
+    }
+
+    // Mate-distance pruning: even a mate on the next movement won't be
+    // better than a shorter mate that we already know about elsewhere.
+    α = α.max(-mating(ply));
+    let β = β.min(mating(ply.saturating_add(1)));
+    if α >= β {
+        return α;
+    }
+
+    if depth <= 0 {
+        if params.quiescence {
+            return quiescence_search(world, α, β, ply, params.quiescent_checks,
+                                     &params.eval, servant_bank, leash, stats,
+                                     foresight);
+        } else {
+            return orientation(world.initiative) *
+                recollect_and_score(world, &params.eval, &servant_bank,
+                                    stats);
+        }
+    }
+    let mut premonitions = world.reckless_lookahead();
+    let mut optimum = NEG_INFINITY;
+
+    // Moves made while the figurehead is in critical endangerment are all
+    // attempts to escape it, and can't be trusted to shallower searches.
+    let endangered = world.in_critical_endangerment(world.initiative);
+
+    // Near the leaves, a static score far enough outside the window (and
+    // far enough from mate that the margins mean anything) can spare us the
+    // search.
+    let frontier = !endangered && depth <= params.margins.maximum_depth &&
+        !α.is_mate() && !β.is_mate();
+    let mut futility_ceiling = INFINITY;
+    if frontier {
+        let static_score = orientation(world.initiative) *
+            recollect_and_score(world, &params.eval, &servant_bank, stats);
+        let depth_factor = i32::from(depth);
+        if static_score - params.margins.reverse_futility * depth_factor >= β {
+            return static_score;
+        }
+        if params.quiescence &&
+            static_score + params.margins.razoring * depth_factor < α {
+            let razored = quiescence_search(
+                world, α, β, ply, params.quiescent_checks, &params.eval,
+                servant_bank.clone(), leash, stats, foresight);
+            if razored < α {
+                return razored;
+            }
+        }
+        futility_ceiling = static_score + params.margins.futility * depth_factor;
+    }
+
+    // Null-move pruning: give the opposition a free move, and if they still
+    // can't bring the score below β, don't bother searching our own moves.
+    // This is unsound in zugzwang, which we guard against by refusing to pass
+    // when down to servants (where zugzwang is common), when the figurehead
+    // is endangered (where passing would be illegal), and twice in a row.
+    // (Nor do we pass in mating lines, where a free move proves nothing.)
+    if predecessor.is_some() && depth >= NULL_MOVE_MINIMUM_DEPTH && !β.is_mate() &&
+        !endangered && !world.is_down_to_servants(world.initiative) {
+        // reduce more aggressively the deeper we are
+        let reduction = if depth > 6 { 3 } else { 2 };
+        // (we only need to know whether passing holds β, so the window can
+        // be as narrow as it gets)
+        let null_value = -α_β_negamax_search(
+            world.null_move(), depth - 1 - reduction, ply + 1,
+            -β, -β + Score(1),
+            memory_bank.clone(), servant_bank.clone(), intuition_bank.clone(),
+            leash, params, None, None, extended, stats, foresight
+        );
+        if null_value >= β {
+            // a mate found after passing isn't one we could actually
+            // deliver, so claim no more than the best score short of mate
+            return null_value.min(Score(MATE.0 - MATE_HORIZON));
+        }
+    }
 
     // Note: if sorting by heuristic were sufficiently expensive, it would, on balance, be better
     // to do so only at the higher levels of the tree. From some minor empiric testing, though,
@@ -307,84 +1291,140 @@ pub fn α_β_negamax_search<T: Memory>(
     // performance impact. So that's not the way to go.
     {
         let experience = intuition_bank.lock();
-        premonitions = order_movements_intuitively(&experience, &mut premonitions)
+        premonitions = order_movements_intuitively(
+            &world, &experience, &mut premonitions, ply, provocation, hunch)
     }
+    // a figurehead with only one way out of endangerment had better take it,
+    // and we had better see where that leads
+    let single_reply = endangered && params.extensions.single_reply > 0.0 &&
+        premonitions.iter()
+        .filter(|c| !c.tree.in_critical_endangerment(world.initiative))
+        .count() == 1;
+    let mut legal_movements = 0;
     for premonition in premonitions {
-        let mut value = NEG_INFINITY;  // can't hurt to be pessimistic
-        let mut memory: T = T::flash(premonition.patch);
-        let cached: bool;
-        let space_time = SpaceTime::new(premonition.tree, depth);
-        {
-            let mut open_vault = memory_bank.lock();
-            let remembered_lodestar_maybe = open_vault.get_mut(&space_time);
-            match remembered_lodestar_maybe {
-                Some(remembered_lodestar) => {
-                    cached = true;
-                    value = remembered_lodestar.score;
-                    memory.recombine(remembered_lodestar.memory.clone());
+        if premonition.tree.in_critical_endangerment(world.initiative) {
+            continue;
+        }
+        let index = legal_movements;
+        legal_movements += 1;
+
+        // Late quiet movements are unlikely to be any good if our
+        // intuitions about ordering are any good, so we can get away with
+        // searching them less deeply, or (near the leaves) not at all.
+        let lateness_exempt = endangered ||
+            premonition.hospitalization.is_some() ||
+            premonition.ascension.is_some();
+        // Nor are quiet movements from a hopeless position near the leaves
+        // (unless they endanger the opposing figurehead).
+        if !lateness_exempt && index > 0 && futility_ceiling <= α &&
+            !premonition.tree.in_critical_endangerment(
+                premonition.tree.initiative) {
+            if futility_ceiling > optimum {
+                optimum = futility_ceiling;
+            }
+            continue;
+        }
+        if !lateness_exempt && depth <= LATE_MOVE_PRUNING_MAXIMUM_DEPTH &&
+            index >= late_move_pruning_threshold(depth) {
+            continue;
+        }
+        let reduction = if !lateness_exempt &&
+            depth >= LATE_MOVE_REDUCTION_MINIMUM_DEPTH &&
+            index >= LATE_MOVE_REDUCTION_MINIMUM_INDEX {
+                if depth >= 5 && index >= 2 * LATE_MOVE_REDUCTION_MINIMUM_INDEX {
+                    2
+                } else {
+                    1
                 }
-                None => { cached = false; }
+            } else {
+                0
             };
-        }
-
-        if !cached {
-            let mut lodestar = α_β_negamax_search(
-                premonition.tree, depth - 1,
-                -β, -α,
-                memory_bank.clone(), intuition_bank.clone(),
-                quiet
-            );
-            lodestar.score *= -1.;  // nega-
-            value = lodestar.score;
-            memory.recombine(lodestar.memory.clone());
-            memory_bank.lock().insert(
-                space_time,
-                lodestar,
-            );
+        let extension = params.extensions.assess(
+            &premonition, predecessor, single_reply, extended);
+        let line_extended = extended + extension;
+        // only the whole plies of accumulated extension count
+        let deepening = (line_extended.floor() - extended.floor()) as i8;
+
+        let mut value = recall_or_search(
+            premonition, depth + deepening - reduction, ply, α, β,
+            memory_bank.clone(), servant_bank.clone(), intuition_bank.clone(),
+            leash, params, line_extended, stats, foresight);
+        if reduction > 0 && value > α {
+            // it's better than we were expecting; see if it holds up under
+            // full scrutiny
+            value = recall_or_search(
+                premonition, depth + deepening, ply, α, β,
+                memory_bank.clone(), servant_bank.clone(),
+                intuition_bank.clone(), leash, params, line_extended, stats,
+                foresight);
         }
 
         if value > optimum {
             optimum = value;
-            optimand = memory;
+            foresight.adopt(ply, premonition.patch);
         }
         if value > α {
             α = value;
         }
         if α >= β {
-            if depth > 0 { // not a quietness extension
-                let mut open_vault = intuition_bank.lock();
-                let intuition = open_vault.entry(premonition.patch)
-                    .or_insert(0);
-                *intuition += 2u32.pow(depth as u32);
+            stats.beta_cutoffs += 1;
+            if index == 0 {
+                stats.first_movement_cutoffs += 1;
             }
+            intuition_bank.lock().remember_cutoff(
+                &premonition, depth, ply, provocation);
             break;  // cutoff!
         }
     }
-    Lodestar::new(optimum, optimand)
+    if legal_movements == 0 {
+        return if endangered {
+            -mating(ply)
+        } else {
+            // deadlock
+            Score(0)
+        };
+    }
+    optimum
 }
 
 
-pub fn déjà_vu_table_size_bound<T: Memory>(gib: f32) -> usize {
+// The servant-structure table gets this much of the memory budget, the
+// déjà vu table the rest: there are far fewer servant structures than worlds
+// worth remembering.
+const SERVANT_TABLE_SHARE: f32 = 1. / 32.;
 
-    let bound = usize::from(Bytes::gibi(gib)) /
-        (mem::size_of::<SpaceTime>() + mem::size_of::<Lodestar<T>>());
+pub fn déjà_vu_table_size_bound(gib: f32) -> usize {
+
+    let bound = usize::from(Bytes::gibi(gib * (1. - SERVANT_TABLE_SHARE))) /
+        (size_of::<SpaceTime>() + size_of::<Lodestar>());
     bound
 }
 
+pub fn servant_table_size_bound(gib: f32) -> usize {
+    usize::from(Bytes::gibi(gib * SERVANT_TABLE_SHARE)) /
+        (size_of::<(Pinfield, Pinfield)>() +
+         size_of::<ServantStructure>())
+}
+
 
 pub fn potentially_timebound_kickoff<T: 'static + Memory>(
     world: &WorldState, depth: u8,
-    extension_maybe: Option<u8>,
+    params: SearchParams,
     nihilistically: bool,
-    deadline_maybe: Option<time::Timespec>,
-    intuition_bank: Arc<parking_lot::Mutex<fnv::FnvHashMap<Patch, u32>>>,
+    leash: Arc<Leash>,
+    intuition_bank: Arc<parking_lot::Mutex<Intuition>>,
     déjà_vu_bound: f32)
-        -> Option<Vec<(Commit, f32, T)>> {
-    let déjà_vu_table: LruCache<SpaceTime, Lodestar<T>,
+        -> Option<(Vec<(Commit, Score, T)>, SearchStats)> {
+    let déjà_vu_table: LruCache<SpaceTime, Lodestar,
                                 BuildHasherDefault<XxHash>> =
-        LruCache::with_hash_state(déjà_vu_table_size_bound::<T>(déjà_vu_bound),
+        LruCache::with_hash_state(déjà_vu_table_size_bound(déjà_vu_bound),
                                   Default::default());
     let memory_bank = Arc::new(parking_lot::Mutex::new(déjà_vu_table));
+    let servant_table: LruCache<(Pinfield, Pinfield), ServantStructure,
+                                BuildHasherDefault<XxHash>> =
+        LruCache::with_hash_state(servant_table_size_bound(déjà_vu_bound),
+                                  Default::default());
+    let servant_bank = Arc::new(parking_lot::Mutex::new(servant_table));
     let mut premonitions = if nihilistically {
         world.reckless_lookahead()
     } else {
@@ -392,40 +1432,80 @@ pub fn potentially_timebound_kickoff<T: 'static + Memory>(
     };
     {
         let experience = intuition_bank.lock();
-        premonitions = order_movements_intuitively(&experience, &mut premonitions)
+        premonitions = order_movements_intuitively(
+            world, &experience, &mut premonitions, 0, None, None)
     }
     let mut forecasts = Vec::with_capacity(40);
-    let mut time_radios: Vec<(Commit, mpsc::Receiver<Lodestar<T>>)> = Vec::new();
+    let mut stats = SearchStats::default();
+    stats.visit(0);
+    if params.deterministic {
+        let mut foresight = Foresight::new();
+        for &premonition in &premonitions {
+            let mut travel_stats = SearchStats::default();
+            let value = -α_β_negamax_search(
+                premonition.tree, (depth - 1) as i8, 1,
+                NEG_INFINITY, INFINITY,
+                memory_bank.clone(), servant_bank.clone(),
+                intuition_bank.clone(), &leash,
+                params, Some(premonition), None, 0.0, &mut travel_stats,
+                &mut foresight
+            );
+            if leash.yanked() {
+                return None;
+            }
+            stats.absorb(travel_stats);
+            forecasts.push((premonition, value,
+                            foresight.envision(premonition.patch)));
+        }
+        forecasts.sort_by(|a, b| b.1.cmp(&a.1));
+        return Some((forecasts, stats));
+    }
+    // Each first movement gets its own copy of our intuitions: threads
+    // sharing killers and history mid-search would order each other's
+    // subtrees by cutoffs from unrelated positions, and the late-movement
+    // pruning that trusts that ordering would then disagree from run to
+    // run about which lines are worth looking at.
+    let inherited = intuition_bank.lock().clone();
+    let mut time_radios: Vec<(Commit, mpsc::Receiver<(Score, T, SearchStats,
+                                                      Intuition)>)> =
+        Vec::new();
     for &premonition in &premonitions {
         let travel_memory_bank = memory_bank.clone();
-        let travel_intuition_bank = intuition_bank.clone();
+        let travel_servant_bank = servant_bank.clone();
+        let travel_intuition_bank =
+            Arc::new(parking_lot::Mutex::new(inherited.clone()));
+        let travel_leash = leash.clone();
         let (tx, rx) = mpsc::channel();
         let explorer_radio = tx.clone();
         time_radios.push((premonition, rx));
         thread::spawn(move || {
-            let search_hit: Lodestar<T> = α_β_negamax_search(
-                premonition.tree, (depth - 1) as i8,
+            let mut travel_stats = SearchStats::default();
+            let mut foresight = Foresight::new();
+            let value = -α_β_negamax_search(
+                premonition.tree, (depth - 1) as i8, 1,
                 NEG_INFINITY, INFINITY,
-                travel_memory_bank, travel_intuition_bank,
-                extension_maybe
+                travel_memory_bank, travel_servant_bank,
+                travel_intuition_bank.clone(), &travel_leash,
+                params, Some(premonition), None, 0.0, &mut travel_stats,
+                &mut foresight
             );
-            explorer_radio.send(search_hit).ok();
+            let variation: T = foresight.envision(premonition.patch);
+            let learned = travel_intuition_bank.lock().clone();
+            explorer_radio.send((value, variation, travel_stats, learned)).ok();
         });
     }
     while !time_radios.is_empty() {  // polling for results
-        if let Some(deadline) = deadline_maybe {
-            if time::get_time() > deadline {
-                return None;
-            }
+        if leash.yanked() {
+            return None;
         }
         // iterate over indices so that we can use swap_remove during the loop
         for i in (0..time_radios.len()).rev() {
             let premonition = time_radios[i].0;
-            if let Ok(search_hit) = time_radios[i].1.try_recv() {
-                let value = -search_hit.score;
-                let mut full_variation = T::flash(premonition.patch);
-                full_variation.recombine(search_hit.memory);
-                forecasts.push((premonition, value, full_variation));
+            if let Ok((value, variation, travel_stats, learned)) =
+                    time_radios[i].1.try_recv() {
+                stats.absorb(travel_stats);
+                intuition_bank.lock().absorb(&learned, &inherited);
+                forecasts.push((premonition, value, variation));
                 time_radios.swap_remove(i);
             }
         }
@@ -433,60 +1513,128 @@ pub fn potentially_timebound_kickoff<T: 'static + Memory>(
         debug!("waiting for {} of {} first-movement search threads",
                time_radios.len(), premonitions.len())
     }
-    forecasts.sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
-    Some(forecasts)
+    forecasts.sort_unstable_by(|a, b| b.1.cmp(&a.1));
+    Some((forecasts, stats))
 }
 
 
-pub fn kickoff<T: 'static + Memory>(world: &WorldState, depth: u8, extension: Option<u8>,
+pub fn kickoff<T: 'static + Memory>(world: &WorldState, depth: u8,
+               params: SearchParams,
                nihilistically: bool, déjà_vu_bound: f32)
-                   -> Vec<(Commit, f32, T)> {
-    let experience_table: fnv::FnvHashMap<Patch, u32> = fnv::FnvHashMap::default();
-    let intuition_bank = Arc::new(parking_lot::Mutex::new(experience_table));
-    potentially_timebound_kickoff::<T>(world, depth, extension, nihilistically, None,
+                   -> (Vec<(Commit, Score, T)>, SearchStats) {
+    let intuition_bank = Arc::new(parking_lot::Mutex::new(Intuition::new()));
+    potentially_timebound_kickoff::<T>(world, depth, params, nihilistically,
+                                  Arc::new(Leash::default()),
                                   intuition_bank, déjà_vu_bound).unwrap()
 }
 
 
-pub fn iterative_deepening_kickoff<T: 'static + Memory>(world: &WorldState, timeout: time::Duration,
+pub fn iterative_deepening_kickoff<T: 'static + Memory>(world: &WorldState,
+                                   curfew: Curfew,
+                                   params: SearchParams,
                                    nihilistically: bool, déjà_vu_bound: f32)
-                                   -> (Vec<(Commit, f32, T)>, u8) {
-    let deadline = time::get_time() + timeout;
+                                   -> (Vec<(Commit, Score, T)>, u8, SearchStats) {
+    let leash = Arc::new(Leash::from_curfew(&curfew));
+    leashed_iterative_deepening_kickoff(world, curfew, leash, params,
+                                        nihilistically, déjà_vu_bound)
+}
+
+
+/// Deepen iteratively on a leash that someone else might yank, whose
+/// deadline and node budget stand in for the curfew's.
+pub fn leashed_iterative_deepening_kickoff<T: 'static + Memory>(
+    world: &WorldState, curfew: Curfew, leash: Arc<Leash>,
+    params: SearchParams, nihilistically: bool, déjà_vu_bound: f32)
+        -> (Vec<(Commit, Score, T)>, u8, SearchStats) {
+    let mut hourglass = curfew.hourglass.map(|h| (time::get_time(), h));
     let mut depth = 1;
-    let experience_table = fnv::FnvHashMap::default();
-    let intuition_bank = Arc::new(parking_lot::Mutex::new(experience_table));
-    let mut forecasts = potentially_timebound_kickoff(
-        world, depth, None, nihilistically, None,
-        intuition_bank.clone(),
-        déjà_vu_bound).unwrap();
-    while let Some(prophecy) = potentially_timebound_kickoff::<T>(
-            world, depth, None, nihilistically, Some(deadline),
+    let intuition_bank = Arc::new(parking_lot::Mutex::new(Intuition::new()));
+    let (mut forecasts, mut stats) = match potentially_timebound_kickoff(
+            world, depth, params, nihilistically, leash.clone(),
             intuition_bank.clone(), déjà_vu_bound) {
-        forecasts = prophecy;
-        depth += 1;
+        Some(foretold) => foretold,
+        None => {
+            // whatever the curfew, we need to have something to say, if
+            // only what we think of each movement at a glance
+            let glance = orientation(world.initiative);
+            let mut forecasts = if nihilistically {
+                world.reckless_lookahead()
+            } else {
+                world.lookahead()
+            }.into_iter()
+                .map(|c| {
+                    let skeleton = judgment::servant_skeleton(
+                        c.tree.orange_servants, c.tree.blue_servants);
+                    (c, glance * assess(c.tree, skeleton, &params.eval),
+                     T::flash(c.patch))
+                })
+                .collect::<Vec<_>>();
+            forecasts.sort_by(|a, b| b.1.cmp(&a.1));
+            return (forecasts, 0, SearchStats::default());
+        }
+    };
+    while depth < curfew.depth.unwrap_or(u8::max_value()) {
+        if let Some(movements) = curfew.mate_in {
+            let mated = forecasts.first()
+                .and_then(|f| f.1.mate_in())
+                .map_or(false, |m| m > 0 && m <= i16::from(movements));
+            // a mate in n movements is 2n − 1 plies away
+            if mated || u16::from(depth) >= 2 * u16::from(movements) {
+                break;
+            }
+        }
+        if hourglass.is_none() {
+            hourglass = leash.imposition();
+        }
+        if let Some((start_thinking, ref mut hourglass)) = hourglass {
+            let persevering = match forecasts.first() {
+                Some(&(ref best, score, _)) => hourglass.persevere(
+                    time::get_time() - start_thinking, best.patch, score,
+                    forecasts.get(1).map(|f| f.1)),
+                None => false,
+            };
+            if !persevering {
+                break;
+            }
+        }
+        intuition_bank.lock().age();
+        match potentially_timebound_kickoff::<T>(
+                world, depth + 1, params, nihilistically, leash.clone(),
+                intuition_bank.clone(), déjà_vu_bound) {
+            Some((prophecy, prophecy_stats)) => {
+                forecasts = prophecy;
+                stats.absorb(prophecy_stats);
+                depth += 1;
+            }
+            None => break,
+        }
     }
-    (forecasts, depth-1)
+    (forecasts, depth, stats)
 }
 
 
 #[allow(needless_pass_by_value)] // `depth_sequence`
 pub fn fixed_depth_sequence_kickoff<T: 'static + Memory>(world: &WorldState, depth_sequence: Vec<u8>,
+                                    params: SearchParams,
                                     nihilistically: bool, déjà_vu_bound: f32)
-                                    -> Vec<(Commit, f32, T)> {
+                                    -> (Vec<(Commit, Score, T)>, SearchStats) {
     let mut depths = depth_sequence.iter();
-    let experience_table = fnv::FnvHashMap::default();
-    let intuition_bank = Arc::new(parking_lot::Mutex::new(experience_table));
-    let mut forecasts = potentially_timebound_kickoff::<T>(
+    let intuition_bank = Arc::new(parking_lot::Mutex::new(Intuition::new()));
+    let (mut forecasts, mut stats) = potentially_timebound_kickoff::<T>(
         world, *depths.next().expect("`depth_sequence` should be nonempty"),
-        None, nihilistically, None, intuition_bank.clone(),
+        params, nihilistically, Arc::new(Leash::default()),
+        intuition_bank.clone(),
         déjà_vu_bound
     ).unwrap();
     for &depth in depths {
-        forecasts = potentially_timebound_kickoff::<T>(
-            world, depth, None, nihilistically, None,
+        intuition_bank.lock().age();
+        let (prophecy, prophecy_stats) = potentially_timebound_kickoff::<T>(
+            world, depth, params, nihilistically, Arc::new(Leash::default()),
             intuition_bank.clone(), déjà_vu_bound).unwrap();
+        forecasts = prophecy;
+        stats.absorb(prophecy_stats);
     }
-    forecasts
+    (forecasts, stats)
 }
 
 
@@ -496,17 +1644,44 @@ mod tests {
     use self::test::Bencher;
 
     use time;
-    use super::{REWARD_FOR_INITIATIVE, kickoff, score, SpaceTime, Variation};
+    use super::{kickoff, iterative_deepening_kickoff,
+                leashed_iterative_deepening_kickoff,
+                score, score_breakdown, recollect_and_score,
+                servant_table_size_bound,
+                SpaceTime, Variation, Curfew, Leash,
+                Intuition, order_movements_intuitively, static_exchange,
+                SearchParams, SearchStats, Extensions, Margins, Foresight};
+    use karma::{mating, Score};
+    use scales::EvalParams;
     use space::Locale;
     use life::{WorldState, Patch};
     use fnv;
     use twox_hash::XxHash;
-    use std::hash::Hash;
+    use lru_cache::LruCache;
+    use parking_lot;
+    use std::hash::{BuildHasherDefault, Hash};
+    use std::sync::Arc;
+    use std::thread;
     use std::collections::hash_map;
+    use hourglass::Hourglass;
     use identity::{Agent, JobDescription, Team};
 
     const MOCK_DÉJÀ_VU_BOUND: f32 = 2.0;
 
+    // worlds of all sorts, for checking that neither team is favored
+    static SYMMETRY_CORPUS: [&'static str; 10] = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+        "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -",
+        "r1bqk2r/pppp1Ppp/2n2n2/2b1p3/2B1P3/5N2/PPPP2PP/RNBQK2R b KQkq -",
+        "rnbqkbnr/ppp2ppp/4p3/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6",
+        "3q1rk1/2R1bppp/pP2p3/N2b4/1r6/4BP2/1P1Q2PP/R5K1 b - -",
+        "r4rk1/1pp2ppp/p1np1q2/4p3/2B1P1b1/2NP1N2/PPP2PPP/R2Q1RK1 w - -",
+        "6k1/1R4pp/5p2/8/P7/6P1/5P1P/6K1 w - -",
+        "8/8/6k1/8/P7/8/8/4K3 b - -",
+        "8/5pk1/6p1/3P4/8/6P1/5PK1/8 w - -",
+        "4k3/8/8/3q4/8/8/3R4/4K3 w - -",
+    ];
+
     impl WorldState {
         fn no_castling_at_all(&mut self) {
             self.clear_orange_east_service_eligibility();
@@ -620,25 +1795,29 @@ mod tests {
     #[bench]
     fn benchmark_kickoff_depth_1(b: &mut Bencher) {
         let ws = WorldState::new();
-        b.iter(|| kickoff::<Patch>(&ws, 1, None, true, MOCK_DÉJÀ_VU_BOUND));
+        b.iter(|| kickoff::<Patch>(
+            &ws, 1, SearchParams::default(), true, MOCK_DÉJÀ_VU_BOUND));
     }
 
     #[bench]
     fn benchmark_kickoff_depth_2_arbys(b: &mut Bencher) {
         let ws = WorldState::new();
-        b.iter(|| kickoff::<Patch>(&ws, 2, None, true, MOCK_DÉJÀ_VU_BOUND));
+        b.iter(|| kickoff::<Patch>(
+            &ws, 2, SearchParams::default(), true, MOCK_DÉJÀ_VU_BOUND));
     }
 
     #[bench]
     fn benchmark_kickoff_depth_2_carefully(b: &mut Bencher) {
         let ws = WorldState::new();
-        b.iter(|| kickoff::<Patch>(&ws, 2, None, false, MOCK_DÉJÀ_VU_BOUND));
+        b.iter(|| kickoff::<Patch>(
+            &ws, 2, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND));
     }
 
     #[bench]
     fn benchmark_kickoff_depth_3(b: &mut Bencher) {
         let ws = WorldState::new();
-        b.iter(|| kickoff::<Patch>(&ws, 3, None, true, MOCK_DÉJÀ_VU_BOUND));
+        b.iter(|| kickoff::<Patch>(
+            &ws, 3, SearchParams::default(), true, MOCK_DÉJÀ_VU_BOUND));
     }
 
     #[test]
@@ -646,17 +1825,16 @@ mod tests {
     fn concerning_short_circuiting_upon_finding_critical_endangerment() {
         let ws = WorldState::reconstruct("7K/r7/1r6/8/8/8/8/7k b -");
         let start = time::get_time();
-        kickoff::<Variation>(&ws, 30, None, true, MOCK_DÉJÀ_VU_BOUND);
+        kickoff::<Variation>(&ws, 30, SearchParams::default(), true,
+                             MOCK_DÉJÀ_VU_BOUND);
         let duration = time::get_time() - start;
         assert!(duration.num_seconds() < 20);
     }
 
     #[test]
-    #[allow(float_cmp)]
     fn concerning_fairness_of_the_initial_position() {
-        // It's okay to assume this is really 0.0. Floats may be imprecise,
-        // but they do have well-defined behavior.
-        assert_eq!(0.0, score(WorldState::new()) - REWARD_FOR_INITIATIVE);
+        assert_eq!(Score(0), score(WorldState::new()) -
+                   EvalParams::default().initiative);
     }
 
     #[test]
@@ -666,11 +1844,12 @@ mod tests {
         // split, whereby transforming into a pony (rather than
         // transitioning into a princess, as would usually be
         // expected) endangers both the blue princess and figurehead
-        let tops = kickoff::<Variation>(&ws, 3, None, true, MOCK_DÉJÀ_VU_BOUND);
+        let (tops, _) = kickoff::<Variation>(
+            &ws, 3, SearchParams::default(), true, MOCK_DÉJÀ_VU_BOUND);
         let best_move = tops[0].0;
         let score = tops[0].1;
         println!("{:?}", best_move);
-        assert!(score > 0.0);
+        assert!(score > Score(0));
         assert_eq!(best_move.tree.preserve(), "2N5/q3k3/8/8/8/8/6PP/7K b - -");
     }
 
@@ -693,44 +1872,26 @@ mod tests {
         // Blue has another servant sitting nowhere interesting
         world.blue_servants = world.blue_servants.alight(Locale::new(3, 6));
         world.no_castling_at_all();
+        world.recount();
 
         let depth = 2;
-        let advisory = kickoff::<Variation>(&world, depth, None, true, MOCK_DÉJÀ_VU_BOUND);
+        let params = SearchParams { deterministic: true, ..SearchParams::default() };
+        let (advisory, _) = kickoff::<Variation>(
+            &world, depth, params, true, MOCK_DÉJÀ_VU_BOUND);
 
         // taking the pony is the right thing to do
         assert_eq!(Locale::new(0, 0), advisory[0].0.patch.whither);
 
         // And, furthermore, the answer should be the same if we face the
         // same situation with the colors reversed
-        //
-        // XXX this would be tidier and less copy-pastey if I had more
-        // general figurine-placing functions that were three rather than
-        // two levels of abstraction above twiddling bits on an unsigned
-        // int ... oh, well
-        let mut negaworld = WorldState::new_except_empty();
-        negaworld.initiative = Team::Blue;
-
-        // scholar endangers pony
-        negaworld.orange_ponies = negaworld.orange_ponies.alight(Locale::new(0, 0));
-        negaworld.blue_scholars = negaworld.blue_scholars.alight(Locale::new(2, 2));
-
-        // pony endangers servant
-        negaworld.orange_servants = negaworld.orange_servants
-                                             .alight(Locale::new(7, 1));
-        negaworld.blue_ponies = negaworld.blue_ponies.alight(Locale::new(5, 2));
-
-        // Orange has another servant sitting nowhere interesting
-        negaworld.orange_servants = negaworld.orange_servants
-                                             .alight(Locale::new(3, 6));
-        negaworld.initiative = Team::Blue;
-
-        negaworld.no_castling_at_all();
-
-        let negadvisory = kickoff::<Variation>(&negaworld, depth, None, true, MOCK_DÉJÀ_VU_BOUND);
+        let negaworld = world.mirrored();
+        let (negadvisory, _) = kickoff::<Variation>(
+            &negaworld, depth, params, true, MOCK_DÉJÀ_VU_BOUND);
 
         // taking the pony is still the right thing to do, even in the
         // negaworld
-        assert_eq!(Locale::new(0, 0), negadvisory[0].0.patch.whither);
+        assert_eq!(Locale::new(7, 0), negadvisory[0].0.patch.whither);
+        assert_eq!(advisory[0].1, negadvisory[0].1);
     }
 
     #[ignore]  // really slow
@@ -764,10 +1925,12 @@ mod tests {
             let world = WorldState::reconstruct(world_runeset);
             let mut previously = None;
             for &depth in &[2, 3, 4] {
-                let premonitions = kickoff::<Variation>(&world, depth, None, true, 1.0);
+                let (premonitions, _) = kickoff::<Variation>(
+                    &world, depth, SearchParams::default(), true, 1.0);
                 let mut top_showings = 0.;
                 for showing in &premonitions[0..10] {
-                    top_showings += showing.1; // (_commit, score, _variation)
+                    // (_commit, score, _variation)
+                    top_showings += showing.1.in_servants();
                 }
                 let club_score = top_showings / 10.;
                 if let Some(previous_score) = previously {
@@ -783,7 +1946,8 @@ mod tests {
             tempo_lurches.iter().sum::<f32>()/tempo_lurches.len() as f32;
         println!("tempo lurches were {:?}, average was {}",
                  tempo_lurches, average_tempo_lurch);
-        assert_eq_within_ε!(REWARD_FOR_INITIATIVE, average_tempo_lurch, 0.8);
+        assert_eq_within_ε!(EvalParams::default().initiative.in_servants(),
+                            average_tempo_lurch, 0.8);
     }
 
     #[test]
@@ -792,4 +1956,411 @@ mod tests {
         let orange_not_doubled = WorldState::reconstruct("k7/pp6/8/8/8/8/PP6/K7 w - -");
         assert!(score(orange_doubled) < score(orange_not_doubled));
     }
+
+    #[test]
+    fn concerning_killers_and_countermoves() {
+        let world = WorldState::new();
+        let mut premonitions = world.lookahead();
+        let provocation = Patch {
+            star: Agent::new(Team::Blue, JobDescription::Servant),
+            whence: Locale::from_algebraic("e7"),
+            whither: Locale::from_algebraic("e5"),
+        };
+        let mut intuition = Intuition::new();
+        let killer = premonitions[premonitions.len() - 1];
+        let retort = premonitions[premonitions.len() - 2];
+        intuition.remember_cutoff(&killer, 1, 2, None);
+        intuition.remember_cutoff(&retort, 1, 3, Some(provocation));
+
+        let at_two = order_movements_intuitively(
+            &world, &intuition, &mut premonitions, 2, None, None);
+        assert_eq!(killer.patch, at_two[0].patch);
+        let at_three = order_movements_intuitively(
+            &world, &intuition, &mut premonitions, 3, Some(provocation), None);
+        // at ply three, the retort is the killer (as well as the countermove
+        // to the provocation), so it comes first despite equal history
+        assert_eq!(retort.patch, at_three[0].patch);
+
+        intuition.age();
+        assert_eq!(Some(&1), intuition.history.get(&killer.patch));
+        intuition.age();
+        assert_eq!(None, intuition.history.get(&killer.patch));
+    }
+
+    #[test]
+    fn concerning_static_exchange() {
+        // a princess stunning a defended servant is a bad trade ...
+        let world = WorldState::reconstruct("4k3/8/3p4/4p3/8/8/8/4QK2 w - -");
+        assert_eq!(Score(-780), static_exchange(&world, Patch {
+            star: Agent::new(Team::Orange, JobDescription::Princess),
+            whence: Locale::from_algebraic("e1"),
+            whither: Locale::from_algebraic("e5"),
+        }));
+
+        // ... whereas a free pony is free
+        let world = WorldState::reconstruct("4k3/8/8/3n4/8/4N3/8/4K3 w - -");
+        assert_eq!(Score(320), static_exchange(&world, Patch {
+            star: Agent::new(Team::Orange, JobDescription::Pony),
+            whence: Locale::from_algebraic("e3"),
+            whither: Locale::from_algebraic("d5"),
+        }));
+
+        // and a defended servant is worth stunning if a second cop is
+        // lined up behind the first
+        let world = WorldState::reconstruct("r6k/p7/8/8/8/8/R7/R6K w - -");
+        assert_eq!(Score(100), static_exchange(&world, Patch {
+            star: Agent::new(Team::Orange, JobDescription::Cop),
+            whence: Locale::from_algebraic("a2"),
+            whither: Locale::from_algebraic("a7"),
+        }));
+    }
+
+    #[test]
+    fn concerning_quiescence() {
+        // At depth one, a princess stunning a defended servant looks like a
+        // free servant, unless we settle the ensuing exchange.
+        let world = WorldState::reconstruct("4k3/8/3p4/4p3/8/8/8/4QK2 w - -");
+        let e5 = Locale::from_algebraic("e5");
+        let (credulous, _) = kickoff::<Variation>(
+            &world, 1, SearchParams { quiescence: false, ..SearchParams::default() },
+            false, MOCK_DÉJÀ_VU_BOUND);
+        assert_eq!(e5, credulous[0].0.patch.whither);
+        let (circumspect, _) = kickoff::<Variation>(
+            &world, 1, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        assert!(circumspect[0].0.patch.whither != e5);
+    }
+
+    #[test]
+    fn concerning_mate_distance() {
+        // a classic back-rank mate
+        let world = WorldState::reconstruct("6k1/5ppp/8/8/8/8/8/R5K1 w - -");
+        let (forecasts, _) = kickoff::<Variation>(
+            &world, 2, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        assert_eq!(Locale::from_algebraic("a8"), forecasts[0].0.patch.whither);
+        assert_eq!(Some(1), forecasts[0].1.mate_in());
+        assert_eq!(mating(1), forecasts[0].1);
+    }
+
+    #[test]
+    fn concerning_deadlock() {
+        // Blue's figurehead has nowhere to go, so most of Orange's movements
+        // leave Blue deadlocked, which is a draw (and much worse for Orange
+        // than being up a princess).
+        let world = WorldState::reconstruct("k7/2Q5/8/8/8/8/8/7K w - -");
+        let (forecasts, _) = kickoff::<Variation>(
+            &world, 2, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        let standstill = forecasts.iter()
+            .find(|f| f.0.patch.whither == Locale::from_algebraic("g1"))
+            .unwrap();
+        assert_eq!(Score(0), standstill.1);
+    }
+
+    #[test]
+    fn concerning_extensions() {
+        let world = WorldState::reconstruct("k7/8/4P3/8/8/8/8/4K2R w - -");
+        let premonitions = world.lookahead();
+        let movement = |whither: &str| {
+            *premonitions.iter()
+                .find(|c| c.patch.whither == Locale::from_algebraic(whither))
+                .unwrap()
+        };
+        let extensions = Extensions::default();
+        // endangering the figurehead gets a whole ply, a servant closing in
+        // on ascension half of one, and a quiet cop movement nothing
+        assert_eq_within_ε!(1.0, extensions.assess(
+            &movement("h8"), None, false, 0.0), 0.001);
+        assert_eq_within_ε!(0.5, extensions.assess(
+            &movement("e7"), None, false, 0.0), 0.001);
+        assert_eq_within_ε!(0.0, extensions.assess(
+            &movement("h5"), None, false, 0.0), 0.001);
+        // but not past the budget
+        assert_eq_within_ε!(0.25, extensions.assess(
+            &movement("h8"), None, false, extensions.budget - 0.25), 0.001);
+        let abstemious = Extensions { budget: 0.0, ..Extensions::default() };
+        assert_eq_within_ε!(0.0, abstemious.assess(
+            &movement("h8"), None, true, 0.0), 0.001);
+    }
+
+    #[test]
+    fn concerning_frontier_pruning() {
+        // pruning near the leaves shouldn't cost us a back-rank mate ...
+        let world = WorldState::reconstruct("6k1/5ppp/8/8/8/8/8/R5K1 w - -");
+        let (forecasts, _) = kickoff::<Variation>(
+            &world, 4, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        assert_eq!(Locale::from_algebraic("a8"), forecasts[0].0.patch.whither);
+        assert_eq!(Some(1), forecasts[0].1.mate_in());
+
+        // ... or send the princess into a defended servant ...
+        let world = WorldState::reconstruct("4k3/8/3p4/4p3/8/8/8/4QK2 w - -");
+        let (forecasts, _) = kickoff::<Variation>(
+            &world, 4, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        assert!(forecasts[0].0.patch.whither != Locale::from_algebraic("e5"));
+
+        // ... and shouldn't change our minds about an opening much
+        let world = WorldState::reconstruct(
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -");
+        let careful = SearchParams {
+            margins: Margins { maximum_depth: 0, ..Margins::default() },
+            ..SearchParams::default()
+        };
+        let (hasty, _) = kickoff::<Variation>(
+            &world, 4, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        let (thorough, _) = kickoff::<Variation>(
+            &world, 4, careful, false, MOCK_DÉJÀ_VU_BOUND);
+        assert!((thorough[0].1 - hasty[0].1).abs() <= Score(50));
+    }
+
+    #[test]
+    fn concerning_search_stats() {
+        let world = WorldState::reconstruct(
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -");
+        let (_, stats) = kickoff::<Variation>(
+            &world, 4, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        assert!(stats.nodes > 0 && stats.quiescence_nodes > 0);
+        assert_eq!(stats.nodes + stats.quiescence_nodes, stats.all_nodes());
+        assert!(stats.memory_hits <= stats.memory_probes);
+        assert!(stats.memory_overwrites <= stats.memory_stores);
+        // the servants rarely move, so their structures are mostly familiar
+        assert!(stats.servant_hits > stats.servant_probes / 2);
+        assert!(stats.first_movement_cutoffs <= stats.beta_cutoffs);
+        assert!(stats.selective_depth >= 4);
+
+        let mut twice = stats;
+        twice.absorb(stats);
+        assert_eq!(2 * stats.nodes, twice.nodes);
+        assert_eq!(stats.selective_depth, twice.selective_depth);
+        assert_eq_within_ε!(stats.first_movement_cutoff_rate(),
+                            twice.first_movement_cutoff_rate(), 0.0001);
+        assert_eq!(0.0, SearchStats::default().memory_hit_rate());
+    }
+
+    #[test]
+    fn concerning_evenhandedness() {
+        for runes in &SYMMETRY_CORPUS {
+            let world = WorldState::reconstruct(runes);
+            assert_eq!(score(world), -score(world.mirrored()),
+                       "{} is scored unlike its mirror image", runes);
+        }
+    }
+
+    #[test]
+    fn concerning_evenhanded_search() {
+        let params = SearchParams { deterministic: true, ..SearchParams::default() };
+        for runes in &SYMMETRY_CORPUS {
+            let world = WorldState::reconstruct(runes);
+            let (forecasts, _) = kickoff::<Variation>(
+                &world, 3, params, false, MOCK_DÉJÀ_VU_BOUND);
+            let (mirrored_forecasts, _) = kickoff::<Variation>(
+                &world.mirrored(), 3, params, false, MOCK_DÉJÀ_VU_BOUND);
+            assert_eq!(forecasts[0].1, mirrored_forecasts[0].1,
+                       "{} is searched unlike its mirror image", runes);
+            assert_eq!(forecasts[0].0.tree.mirrored(),
+                       mirrored_forecasts[0].0.tree);
+        }
+    }
+
+    #[test]
+    fn concerning_score_breakdowns() {
+        let eval = EvalParams::default();
+        for runes in &[
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+                "r1bqk2r/pppp1Ppp/2n2n2/2b1p3/2B1P3/5N2/PPPP2PP/RNBQK2R b KQkq -",
+                "6k1/1R3ppp/8/8/P7/6P1/5P1P/6K1 w - -",
+                "8/8/6k1/8/P7/8/8/4K3 b - -"] {
+            let world = WorldState::reconstruct(runes);
+            let breakdown = score_breakdown(world, &eval);
+            let total = breakdown.iter()
+                .fold(Score(0), |total, consideration| total + consideration.3);
+            assert_eq!(score(world), total);
+        }
+
+        let breakdown = score_breakdown(WorldState::new(), &eval);
+        assert_eq!(("material", Score(4000), Score(4000), Score(0)),
+                   breakdown[1]);
+        for &(_, orange, blue, total) in &breakdown[1..] {
+            assert_eq!(orange, blue);
+            assert_eq!(Score(0), total);
+        }
+
+        let world = WorldState::reconstruct(
+            "6k1/1R3ppp/8/8/P7/6P1/5P1P/6K1 w - -");
+        let breakdown = score_breakdown(world, &eval);
+        let cop_beat = breakdown.iter().find(|c| c.0 == "cop beat").unwrap();
+        assert_eq!(("cop beat", Score(50), Score(0), Score(50)), *cop_beat);
+    }
+
+    #[test]
+    fn concerning_déjà_vu_bounds() {
+        // A score remembered from a search that failed high or low is only
+        // a bound, which mustn't be mistaken for the exact score: without the
+        // heuristics that let the window shape the search (and so long as
+        // the movements come in the same order), remembering shouldn't
+        // change what we find.
+        let world = WorldState::reconstruct(
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -");
+        let params = SearchParams {
+            quiescence: false,
+            extensions: Extensions { budget: 0., ..Extensions::default() },
+            margins: Margins { maximum_depth: 0, ..Margins::default() },
+            deterministic: true,
+            ..SearchParams::default()
+        };
+        let forecast = |déjà_vu_bound| {
+            let (forecasts, _) = kickoff::<Variation>(
+                &world, 5, params, false, déjà_vu_bound);
+            let mut scores = forecasts.iter()
+                .map(|&(ref commit, score, _)| (commit.patch.whither.pindex(),
+                                                commit.patch.whence.pindex(),
+                                                score))
+                .collect::<Vec<_>>();
+            scores.sort();
+            scores
+        };
+        assert_eq!(forecast(0.), forecast(MOCK_DÉJÀ_VU_BOUND));
+    }
+
+    #[test]
+    fn concerning_the_servant_table() {
+        let servant_bank = Arc::new(parking_lot::Mutex::new(
+            LruCache::<_, _, BuildHasherDefault<XxHash>>::with_hash_state(
+                servant_table_size_bound(MOCK_DÉJÀ_VU_BOUND),
+                Default::default())));
+        let mut stats = SearchStats::default();
+        let world = WorldState::reconstruct(
+            "4k3/pp3p2/8/3P4/8/8/5PPP/4K3 w - -");
+        let sibling = WorldState::reconstruct(
+            "4k3/pp3p2/8/3P4/8/8/5PPP/3K4 b - -");
+        let eval = EvalParams::default();
+        assert_eq!(score(world), recollect_and_score(world, &eval, &servant_bank,
+                                                     &mut stats));
+        assert_eq!(0, stats.servant_hits);
+        // a world with the same servants needn't work them out again
+        assert_eq!(score(sibling), recollect_and_score(sibling, &eval,
+                                                       &servant_bank,
+                                                       &mut stats));
+        assert_eq!(1, stats.servant_hits);
+        assert_eq!(2, stats.servant_probes);
+        assert!(servant_table_size_bound(MOCK_DÉJÀ_VU_BOUND) > 0);
+    }
+
+    #[test]
+    fn concerning_determinism() {
+        let world = WorldState::reconstruct(
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -");
+        let params = SearchParams { deterministic: true, ..SearchParams::default() };
+        let (forecasts, stats) = kickoff::<Variation>(
+            &world, 4, params, false, MOCK_DÉJÀ_VU_BOUND);
+        for _ in 0..3 {
+            let (reforecasts, restats) = kickoff::<Variation>(
+                &world, 4, params, false, MOCK_DÉJÀ_VU_BOUND);
+            assert_eq!(stats, restats);
+            assert_eq!(forecasts.len(), reforecasts.len());
+            for (forecast, reforecast) in forecasts.iter().zip(&reforecasts) {
+                assert_eq!(forecast.0, reforecast.0);
+                assert_eq!(forecast.1, reforecast.1);
+                assert_eq!(forecast.2, reforecast.2);
+            }
+        }
+    }
+
+    #[test]
+    fn concerning_curfews() {
+        let world = WorldState::reconstruct(
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -");
+        let params = SearchParams { deterministic: true, ..SearchParams::default() };
+        let curfew = Curfew { nodes: Some(20000), ..Curfew::default() };
+        let (forecasts, depth, stats) = iterative_deepening_kickoff::<Variation>(
+            &world, curfew, params, false, MOCK_DÉJÀ_VU_BOUND);
+        assert!(!forecasts.is_empty());
+        assert!(depth > 1);
+        assert!(stats.all_nodes() <= 20000);
+        // and a node budget is as reproducible as the search it bounds
+        let (_, redepth, restats) = iterative_deepening_kickoff::<Variation>(
+            &world, curfew, params, false, MOCK_DÉJÀ_VU_BOUND);
+        assert_eq!((depth, stats), (redepth, restats));
+
+        let before = time::get_time();
+        let curfew = Curfew {
+            timeout: Some(time::Duration::milliseconds(100)),
+            ..Curfew::default()
+        };
+        let (forecasts, _, _) = iterative_deepening_kickoff::<Variation>(
+            &world, curfew, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        assert!(!forecasts.is_empty());
+        assert!(time::get_time() - before < time::Duration::milliseconds(500));
+
+        // a search called off before it began still has something to say
+        let leash = Arc::new(Leash::default());
+        leash.yank();
+        let (forecasts, depth, _) =
+            leashed_iterative_deepening_kickoff::<Variation>(
+                &world, Curfew::default(), leash, params, false,
+                MOCK_DÉJÀ_VU_BOUND);
+        assert_eq!(world.lookahead().len(), forecasts.len());
+        assert_eq!(0, depth);
+
+        // a search that was pondering goes by the clock it's handed later
+        let leash = Arc::new(Leash::default());
+        let travel_leash = leash.clone();
+        let pondering = thread::spawn(move || {
+            leashed_iterative_deepening_kickoff::<Variation>(
+                &world, Curfew::default(), travel_leash,
+                SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND)
+        });
+        thread::sleep(::std::time::Duration::from_millis(50));
+        let before = time::get_time();
+        leash.impose(Hourglass::new(time::Duration::milliseconds(1000),
+                                    time::Duration::zero(), Some(10)));
+        let (forecasts, _, _) = pondering.join()
+            .expect("pondering thread panicked");
+        assert!(!forecasts.is_empty());
+        assert!(time::get_time() - before < time::Duration::milliseconds(600));
+
+        let world = WorldState::reconstruct("6k1/5ppp/8/8/8/8/8/R5K1 w - -");
+        let curfew = Curfew { mate_in: Some(3), ..Curfew::default() };
+        let (forecasts, depth, _) = iterative_deepening_kickoff::<Variation>(
+            &world, curfew, params, false, MOCK_DÉJÀ_VU_BOUND);
+        assert_eq!(Some(1), forecasts[0].1.mate_in());
+        assert!(depth <= 2);
+    }
+
+    #[test]
+    fn concerning_foresight() {
+        let world = WorldState::new();
+        let patches = world.lookahead().iter()
+            .map(|c| c.patch).collect::<Vec<_>>();
+        let mut foresight = Foresight::new();
+        foresight.adopt(3, patches[0]);
+        foresight.adopt(2, patches[1]);
+        foresight.adopt(1, patches[2]);
+        assert_eq!(&[patches[2], patches[1], patches[0]], foresight.line(1));
+        // a better movement at ply 2 doesn't disturb the line from ply 1
+        // until it's adopted there
+        foresight.forget(3);
+        foresight.adopt(2, patches[3]);
+        assert_eq!(&[patches[3]], foresight.line(2));
+        assert_eq!(3, foresight.line(1).len());
+        foresight.recall(2, None);
+        assert!(foresight.line(2).is_empty());
+        // and nobody minds lines too deep to keep track of
+        foresight.adopt(200, patches[0]);
+        assert!(foresight.line(200).is_empty());
+
+        // what comes back from a search is a whole line of play
+        let world = WorldState::reconstruct(
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -");
+        let (forecasts, _) = kickoff::<Variation>(
+            &world, 4, SearchParams::default(), false, MOCK_DÉJÀ_VU_BOUND);
+        assert!(forecasts[0].2.len() >= 2);
+        for &(ref determination, _, ref variation) in &forecasts {
+            assert_eq!(determination.patch, variation[0]);
+            let mut following = world;
+            for &patch in variation {
+                following = following.lookahead().into_iter()
+                    .find(|c| c.patch == patch)
+                    .expect("variation should be playable")
+                    .tree;
+            }
+        }
+    }
 }
@@ -0,0 +1,275 @@
+use std::fs::File;
+use std::hash::BuildHasherDefault;
+use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+use std::thread;
+
+use lru_cache::LruCache;
+use parking_lot;
+use twox_hash::XxHash;
+
+use identity::JobDescription;
+use judgment::{self, ServantStructure};
+use karma::{Score, INFINITY, NEG_INFINITY};
+use life::WorldState;
+use mind::{assess, quiescence_search, Foresight, Leash, SearchStats};
+use scales::{EvalParams, KNOBS};
+
+
+// Learning evaluation weights from experience, after the "Texel" method:
+// settle each world from a game whose outcome we know, and then nudge the
+// weights, one at a time, so as to better predict the outcomes from the
+// scores.
+
+// how many threads share the work of judging our predictions
+const TUNING_THREADS: usize = 8;
+
+// the nudges we try, coarse to fine
+const NUDGES: [i32; 3] = [16, 4, 1];
+
+
+/// a world from a game whose outcome we know, settled by quiescence so that
+/// evaluation has a fair chance of judging it
+#[derive(Debug, Clone, Copy)]
+pub struct Lesson {
+    world: WorldState,
+    skeleton: ServantStructure,
+    /// how the game went for Orange: 1 for a win, ½ for a draw, 0 for a loss
+    result: f32,
+}
+
+/// Read a line consisting of a book of preservation runes followed by the
+/// result of the game, whether as `1-0`, `0-1`, or `1/2-1/2` or as Orange's
+/// share of the point (`1.0`, `0.5`, `0.0`), possibly quoted, bracketed,
+/// or after a `c9` opcode.
+pub fn parse_lesson(line: &str) -> Result<(WorldState, f32), String> {
+    let mut volumes: Vec<&str> = line.split_whitespace().collect();
+    let outcome = volumes.pop()
+        .ok_or_else(|| "expected runes and a result, got nothing".to_owned())?;
+    let outcome = outcome.trim_matches(|c| "\"[];".contains(c));
+    let result = match outcome {
+        "1-0" => 1.0,
+        "0-1" => 0.0,
+        "1/2-1/2" => 0.5,
+        share => match share.parse::<f32>() {
+            Ok(share) if share >= 0.0 && share <= 1.0 => share,
+            _ => {
+                return Err(format!("couldn't understand result {:?} in {:?}",
+                                   outcome, line));
+            }
+        },
+    };
+    if volumes.last() == Some(&"c9") {
+        volumes.pop();
+    }
+    if volumes.len() < 4 {
+        return Err(format!("expected a book of preservation runes in {:?}",
+                           line));
+    }
+    Ok((WorldState::reconstruct(&volumes.join(" ")), result))
+}
+
+/// Play out the line that quiescence would, so that nothing is left
+/// hanging when we come to score it.
+pub fn settle(world: WorldState) -> WorldState {
+    let servant_bank = Arc::new(parking_lot::Mutex::new(
+        LruCache::<_, _, BuildHasherDefault<XxHash>>::with_hash_state(
+            1 << 10, Default::default())));
+    let mut foresight = Foresight::new();
+    quiescence_search(world, NEG_INFINITY, INFINITY, 0, false,
+                      &EvalParams::default(), servant_bank, &Leash::default(),
+                      &mut SearchStats::default(), &mut foresight);
+    // the line only remembers patches, so look up the commits they stand
+    // for (quiescence only ever ascends to princesshood)
+    let mut settled = world;
+    for &patch in foresight.line(0) {
+        let commit = settled.reckless_lookahead().into_iter()
+            .find(|c| {
+                c.patch == patch &&
+                    c.ascension.map_or(true, |a| {
+                        a.job_description == JobDescription::Princess
+                    })
+            });
+        settled = match commit {
+            Some(commit) => commit.tree,
+            None => moral_panic!("settling line strayed from the lookahead"),
+        };
+    }
+    settled
+}
+
+pub fn learn(world: WorldState, result: f32) -> Lesson {
+    let world = settle(world);
+    let skeleton = judgment::servant_skeleton(world.orange_servants,
+                                              world.blue_servants);
+    Lesson { world, skeleton, result }
+}
+
+pub fn load_lessons(path: &str) -> Result<Vec<Lesson>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("couldn't read {}: {}", path, e))?;
+    let mut lessons = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("couldn't read {}: {}", path, e))?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (world, result) = parse_lesson(&line)?;
+        lessons.push(learn(world, result));
+    }
+    Ok(lessons)
+}
+
+
+/// How likely Orange is to win (counting draws as half) given a score, for
+/// the given steepness of belief.
+pub fn win_probability(score: Score, steepness: f32) -> f32 {
+    1. / (1. + 10f32.powf(-steepness * score.in_servants() / 4.))
+}
+
+/// the mean squared difference between the outcomes of the lessons and
+/// what the scores (under the given weights) predicted
+pub fn prediction_error(lessons: &Arc<Vec<Lesson>>, eval: EvalParams,
+                        steepness: f32) -> f32 {
+    if lessons.is_empty() {
+        return 0.;
+    }
+    let share = (lessons.len() + TUNING_THREADS - 1) / TUNING_THREADS;
+    let mut workers = Vec::with_capacity(TUNING_THREADS);
+    for worker in 0..TUNING_THREADS {
+        let travel_lessons = lessons.clone();
+        workers.push(thread::spawn(move || {
+            let mut error = 0f64;
+            for lesson in travel_lessons.iter().skip(worker * share).take(share) {
+                let score = assess(lesson.world, lesson.skeleton, &eval);
+                let miss = lesson.result - win_probability(score, steepness);
+                error += f64::from(miss * miss);
+            }
+            error
+        }));
+    }
+    let total: f64 = workers.into_iter()
+        .map(|w| w.join().expect("tuning thread panicked"))
+        .sum();
+    (total / lessons.len() as f64) as f32
+}
+
+/// the steepness of belief under which the weights best predict the
+/// lessons, which the tuning holds fixed
+pub fn fit_steepness(lessons: &Arc<Vec<Lesson>>, eval: EvalParams) -> f32 {
+    let (mut low, mut high) = (0.05f32, 5.0f32);
+    // (the error should be unimodal in the steepness, so we can narrow in
+    // by thirds)
+    for _ in 0..40 {
+        let one_third = low + (high - low) / 3.;
+        let two_thirds = high - (high - low) / 3.;
+        if prediction_error(lessons, eval, one_third) <
+            prediction_error(lessons, eval, two_thirds) {
+            high = two_thirds;
+        } else {
+            low = one_third;
+        }
+    }
+    (low + high) / 2.
+}
+
+/// Nudge each of the weights in turn, keeping the nudges that make for
+/// better predictions, until no nudge helps (or we've gone over all of
+/// them `passes` times for each size of nudge).
+pub fn tune(lessons: &Arc<Vec<Lesson>>, start: EvalParams, passes: usize)
+            -> EvalParams {
+    let steepness = fit_steepness(lessons, start);
+    info!("tuning from {} lessons with steepness {}", lessons.len(), steepness);
+    let mut eval = start;
+    let mut error = prediction_error(lessons, eval, steepness);
+    for &nudge in &NUDGES {
+        for pass in 0..passes {
+            let mut improved = false;
+            for name in &KNOBS {
+                for &direction in &[1, -1] {
+                    let mut candidate = eval;
+                    *candidate.knob_mut(name).expect("knobs should be known") +=
+                        Score(direction * nudge);
+                    let candidate_error =
+                        prediction_error(lessons, candidate, steepness);
+                    if candidate_error < error {
+                        eval = candidate;
+                        error = candidate_error;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+            info!("pass {} with nudges of {}: error {}", pass, nudge, error);
+            if !improved {
+                break;
+            }
+        }
+    }
+    eval
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{fit_steepness, learn, parse_lesson, prediction_error, settle,
+                tune, win_probability};
+    use karma::Score;
+    use life::WorldState;
+    use scales::EvalParams;
+
+    #[test]
+    fn concerning_lessons() {
+        let runes = "4k3/8/8/8/8/8/8/3QK3 w - -";
+        let world = WorldState::reconstruct(runes);
+        for &(line, result) in &[
+                ("4k3/8/8/8/8/8/8/3QK3 w - - 1-0", 1.0),
+                ("4k3/8/8/8/8/8/8/3QK3 w - - 0 1 [0.5]", 0.5),
+                ("4k3/8/8/8/8/8/8/3QK3 w - - c9 \"0-1\";", 0.0)] {
+            assert_eq!(Ok((world, result)), parse_lesson(line));
+        }
+        assert!(parse_lesson("4k3/8/8/8/8/8/8/3QK3 w - - 2-0").is_err());
+        assert!(parse_lesson("1-0").is_err());
+
+        // a princess left for the taking gets taken before we judge
+        let hanging = WorldState::reconstruct("4k3/8/8/3q4/8/8/3R4/4K3 w - -");
+        assert_eq!(0, settle(hanging).blue_princesses.pincount());
+
+        // a servant about to ascend does so, and isn't left on the last rank
+        for &runes in &["4k3/P7/8/8/8/8/7r/4K3 w - -",
+                        "1n2k3/P4B2/8/8/8/8/7r/4K3 b - -"] {
+            let settled = settle(WorldState::reconstruct(runes));
+            assert_eq!(0, settled.orange_servants.pincount());
+            assert_eq!(1, settled.orange_princesses.pincount());
+            assert_eq!(1, settled.blue_cops.pincount());
+        }
+    }
+
+    #[test]
+    fn concerning_predictions() {
+        assert_eq!(0.5, win_probability(Score(0), 1.));
+        assert!(win_probability(Score(300), 1.) > 0.8);
+        assert!((win_probability(Score(-300), 1.) +
+                 win_probability(Score(300), 1.) - 1.).abs() < 0.0001);
+    }
+
+    #[test]
+    fn concerning_tuning() {
+        // if princesses always win, they're worth more than a servant
+        let lessons = Arc::new(vec![
+            learn(WorldState::reconstruct("4k3/8/8/8/8/8/8/3QK3 w - -"), 1.0),
+            learn(WorldState::reconstruct("3qk3/8/8/8/8/8/8/4K3 w - -"), 0.0),
+            learn(WorldState::reconstruct("4k3/8/8/8/8/8/8/4K3 w - -"), 0.5),
+            learn(WorldState::reconstruct("4k3/4p3/8/8/8/8/4P3/4K3 w - -"),
+                  0.5),
+        ]);
+        let start = EvalParams { princess: Score(100), ..EvalParams::default() };
+        let tuned = tune(&lessons, start, 3);
+        assert!(tuned.princess > start.princess);
+        let steepness = fit_steepness(&lessons, start);
+        assert!(prediction_error(&lessons, tuned, steepness) <
+                prediction_error(&lessons, start, steepness));
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use identity::JobDescription;
+use karma::Score;
+
+
+/// The weights with which evaluation balances its considerations against
+/// each other, in hundredths of a servant. (The finer-grained tables of
+/// terrain, mobility, servant structure, and figurehead safety aren't among
+/// them, yet.)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalParams {
+    /// for having the initiative
+    pub initiative: Score,
+    // what the figurines are worth, after Hans Berliner
+    pub servant: Score,
+    pub pony: Score,
+    pub scholar: Score,
+    pub cop: Score,
+    pub princess: Score,
+    /// for having both scholars (or more)
+    pub scholar_pair: Score,
+    /// for each servant or pony in the center of the world
+    pub centrality: Score,
+    /// for each cop on the seventh rank
+    pub cop_beat: Score,
+    /// against each servant beyond the first on a file
+    pub doubled_servant: Score,
+    /// for each servant on the seventh rank ...
+    pub subascension: Score,
+    /// ... and on the sixth
+    pub subsubascension: Score,
+    /// for still being able to enter the secret service
+    pub service_eligibility: Score,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        Self {
+            initiative: Score(50),
+            // en.wikipedia.org/wiki/
+            // Chess_piece_relative_value#Hans_Berliner.27s_system
+            servant: Score(100),
+            pony: Score(320),
+            scholar: Score(330),
+            cop: Score(510),
+            princess: Score(880),
+            scholar_pair: Score(50),
+            centrality: Score(10),
+            cop_beat: Score(50),
+            // Putting a precise number on how bad extra servants on a file
+            // are seems to be quite hard, and a smarter engine might choose
+            // more dynamically, but half-a-point is OK, I think. Wikipedia
+            // has examples where a doubled servant is worth anywhere from .3
+            // to .75 points.
+            doubled_servant: Score(50),
+            subascension: Score(180),
+            subsubascension: Score(60),
+            service_eligibility: Score(10),
+        }
+    }
+}
+
+/// the names of the parameters, as they appear in files
+pub const KNOBS: [&str; 13] = [
+    "initiative", "servant", "pony", "scholar", "cop", "princess",
+    "scholar_pair", "centrality", "cop_beat", "doubled_servant",
+    "subascension", "subsubascension", "service_eligibility"];
+
+impl EvalParams {
+    pub fn knob(&self, name: &str) -> Option<Score> {
+        let mut params = *self;
+        params.knob_mut(name).map(|knob| *knob)
+    }
+
+    pub fn knob_mut(&mut self, name: &str) -> Option<&mut Score> {
+        match name {
+            "initiative" => Some(&mut self.initiative),
+            "servant" => Some(&mut self.servant),
+            "pony" => Some(&mut self.pony),
+            "scholar" => Some(&mut self.scholar),
+            "cop" => Some(&mut self.cop),
+            "princess" => Some(&mut self.princess),
+            "scholar_pair" => Some(&mut self.scholar_pair),
+            "centrality" => Some(&mut self.centrality),
+            "cop_beat" => Some(&mut self.cop_beat),
+            "doubled_servant" => Some(&mut self.doubled_servant),
+            "subascension" => Some(&mut self.subascension),
+            "subsubascension" => Some(&mut self.subsubascension),
+            "service_eligibility" => Some(&mut self.service_eligibility),
+            _ => None,
+        }
+    }
+
+    /// What a figurine is worth to its own team. (Evaluation leaves the
+    /// figurehead's fate to the search.)
+    pub fn valuation(&self, job_description: JobDescription) -> Score {
+        match job_description {
+            JobDescription::Servant => self.servant,
+            JobDescription::Pony => self.pony,
+            JobDescription::Scholar => self.scholar,
+            JobDescription::Cop => self.cop,
+            JobDescription::Princess => self.princess,
+            JobDescription::Figurehead => Score(0),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e| format!("couldn't read {}: {}", path, e))?;
+        text.parse()
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        File::create(path)
+            .and_then(|mut file| write!(file, "{}", self))
+            .map_err(|e| format!("couldn't write {}: {}", path, e))
+    }
+}
+
+/// One parameter to a line, its name and then its value; blank lines and
+/// lines starting with `#` are ignored, and parameters that aren't
+/// mentioned keep their default values.
+impl FromStr for EvalParams {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let mut params = EvalParams::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let name = words.next().expect("nonempty line has a first word");
+            let value = match (words.next(), words.next()) {
+                (Some(value), None) => value,
+                _ => {
+                    return Err(format!("expected a name and a value on line \
+                                        {}, got {:?}", index + 1, line));
+                }
+            };
+            let value = value.parse::<i32>().map_err(|_| {
+                format!("couldn't parse {:?} as a score on line {}",
+                        value, index + 1)
+            })?;
+            match params.knob_mut(name) {
+                Some(knob) => *knob = Score(value),
+                None => {
+                    return Err(format!("unknown evaluation parameter {:?} on \
+                                        line {}", name, index + 1));
+                }
+            }
+        }
+        Ok(params)
+    }
+}
+
+impl fmt::Display for EvalParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# Leafline evaluation parameters, in hundredths of a \
+                     servant")?;
+        for name in &KNOBS {
+            let value = self.knob(name).expect("knobs should be known");
+            writeln!(f, "{} {}", name, value.0)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{EvalParams, KNOBS};
+    use karma::Score;
+
+    #[test]
+    fn concerning_knobs() {
+        let mut params = EvalParams::default();
+        for name in &KNOBS {
+            *params.knob_mut(name).unwrap() += Score(1);
+        }
+        assert_eq!(Score(101), params.servant);
+        assert_eq!(Score(11), params.service_eligibility);
+        assert_eq!(None, params.knob("figurehead"));
+    }
+
+    #[test]
+    fn concerning_the_text_format() {
+        let params = EvalParams { centrality: Score(14), ..EvalParams::default() };
+        assert_eq!(Ok(params), params.to_string().parse());
+
+        let sparse = "# just the one\n\ncentrality 14\n";
+        assert_eq!(Ok(params), sparse.parse());
+
+        assert!("centrality".parse::<EvalParams>().is_err());
+        assert!("centrality fourteen".parse::<EvalParams>().is_err());
+        assert!("centrism 14".parse::<EvalParams>().is_err());
+    }
+}
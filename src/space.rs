@@ -118,6 +118,11 @@ impl Locale {
         self.rank_and_file >> 4
     }
 
+    /// the corresponding locale on the other side of the world
+    pub fn mirrored(&self) -> Self {
+        Locale::new(7 - self.rank(), self.file())
+    }
+
     pub fn file(&self) -> u8 {
         self.rank_and_file & (0b1111)
     }
@@ -199,6 +204,14 @@ impl Pinfield {
         bits.count_ones() as u8
     }
 
+    /// the same pins, on the other side of the world (each rank being a
+    /// byte, reversing the bytes reverses the ranks)
+    #[cfg(test)]
+    pub fn mirrored(&self) -> Pinfield {
+        let Pinfield(bits) = *self;
+        Pinfield(bits.swap_bytes())
+    }
+
     // TODO: convert to Display::fmt
     #[allow(dead_code)]
     pub fn display(&self) {
@@ -438,4 +451,16 @@ mod tests {
         let stage = Pinfield::init(&starters);
         assert_eq!(3, stage.pincount());
     }
+
+    #[test]
+    fn concerning_mirrors() {
+        assert_eq!(Locale::from_algebraic("c6"),
+                   Locale::from_algebraic("c3").mirrored());
+        let starters = vec![Locale::new(1, 2), Locale::new(3, 4)];
+        let stage = Pinfield::init(&starters);
+        let mirrored = Pinfield::init(
+            &starters.iter().map(|l| l.mirrored()).collect::<Vec<_>>());
+        assert_eq!(mirrored, stage.mirrored());
+        assert_eq!(stage, stage.mirrored().mirrored());
+    }
 }
@@ -0,0 +1,229 @@
+use identity::{Agent, JobDescription, Team};
+use karma::Score;
+use life::WorldState;
+
+
+/// The phase of a world with all of its officers: the figurines other than
+/// servants and figureheads count towards it (a pony or scholar 1, a cop 2,
+/// a princess 4) until there are none left, in the endgame.
+pub const OPENING_PHASE: i32 = 24;
+
+pub fn phase(world: &WorldState) -> i32 {
+    // (ascensions can leave more officers than we started with)
+    world.census().phase().min(OPENING_PHASE)
+}
+
+/// Blend what a term is worth in the middlegame and the endgame according
+/// to how far along we are between them.
+pub fn taper(middlegame: Score, endgame: Score, phase: i32) -> Score {
+    Score((middlegame.0 * phase + endgame.0 * (OPENING_PHASE - phase)) /
+          OPENING_PHASE)
+}
+
+
+// Where each job description likes to stand (in hundredths of a servant),
+// as seen from Orange's side of the world: the first row of each table is
+// Orange's eighth rank, and the last is Orange's first.
+
+static SERVANT_TERRAIN: [[i32; 64]; 2] = [
+    [  0,   0,   0,   0,   0,   0,   0,   0,
+      10,  10,  10,  10,  10,  10,  10,  10,
+       5,   5,  10,  15,  15,  10,   5,   5,
+       0,   0,   5,  15,  15,   5,   0,   0,
+       0,   0,   0,  10,  10,   0,   0,   0,
+       5,  -5, -10,   0,   0, -10,  -5,   5,
+       5,  10,  10, -20, -20,  10,  10,   5,
+       0,   0,   0,   0,   0,   0,   0,   0],
+    [  0,   0,   0,   0,   0,   0,   0,   0,
+      40,  40,  40,  40,  40,  40,  40,  40,
+      25,  25,  25,  25,  25,  25,  25,  25,
+      15,  15,  15,  15,  15,  15,  15,  15,
+       5,   5,   5,   5,   5,   5,   5,   5,
+       0,   0,   0,   0,   0,   0,   0,   0,
+       0,   0,   0,   0,   0,   0,   0,   0,
+       0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+static PONY_TERRAIN: [[i32; 64]; 2] = [
+    [-50, -40, -30, -30, -30, -30, -40, -50,
+     -40, -20,   0,   0,   0,   0, -20, -40,
+     -30,   0,  10,  15,  15,  10,   0, -30,
+     -30,   5,  15,  20,  20,  15,   5, -30,
+     -30,   0,  15,  20,  20,  15,   0, -30,
+     -30,   5,  10,  15,  15,  10,   5, -30,
+     -40, -20,   0,   5,   5,   0, -20, -40,
+     -50, -40, -30, -30, -30, -30, -40, -50],
+    [-40, -30, -20, -20, -20, -20, -30, -40,
+     -30, -10,   0,   0,   0,   0, -10, -30,
+     -20,   0,  10,  10,  10,  10,   0, -20,
+     -20,   0,  10,  15,  15,  10,   0, -20,
+     -20,   0,  10,  15,  15,  10,   0, -20,
+     -20,   0,  10,  10,  10,  10,   0, -20,
+     -30, -10,   0,   0,   0,   0, -10, -30,
+     -40, -30, -20, -20, -20, -20, -30, -40],
+];
+
+static SCHOLAR_TERRAIN: [[i32; 64]; 2] = [
+    [-20, -10, -10, -10, -10, -10, -10, -20,
+     -10,   0,   0,   0,   0,   0,   0, -10,
+     -10,   0,   5,  10,  10,   5,   0, -10,
+     -10,   5,   5,  10,  10,   5,   5, -10,
+     -10,   0,  10,  10,  10,  10,   0, -10,
+     -10,  10,  10,  10,  10,  10,  10, -10,
+     -10,   5,   0,   0,   0,   0,   5, -10,
+     -20, -10, -10, -10, -10, -10, -10, -20],
+    [-15, -10,  -5,  -5,  -5,  -5, -10, -15,
+     -10,   0,   0,   0,   0,   0,   0, -10,
+      -5,   0,   5,   5,   5,   5,   0,  -5,
+      -5,   0,   5,  10,  10,   5,   0,  -5,
+      -5,   0,   5,  10,  10,   5,   0,  -5,
+      -5,   0,   5,   5,   5,   5,   0,  -5,
+     -10,   0,   0,   0,   0,   0,   0, -10,
+     -15, -10,  -5,  -5,  -5,  -5, -10, -15],
+];
+
+// (the seventh rank is rewarded separately)
+static COP_TERRAIN: [[i32; 64]; 2] = [
+    [  0,   0,   0,   0,   0,   0,   0,   0,
+       0,   0,   0,   0,   0,   0,   0,   0,
+      -5,   0,   0,   0,   0,   0,   0,  -5,
+      -5,   0,   0,   0,   0,   0,   0,  -5,
+      -5,   0,   0,   0,   0,   0,   0,  -5,
+      -5,   0,   0,   0,   0,   0,   0,  -5,
+      -5,   0,   0,   0,   0,   0,   0,  -5,
+       0,   0,   0,   5,   5,   0,   0,   0],
+    [  0,   0,   0,   0,   0,   0,   0,   0,
+       0,   0,   0,   0,   0,   0,   0,   0,
+       0,   0,   5,   5,   5,   5,   0,   0,
+       0,   0,   5,   5,   5,   5,   0,   0,
+       0,   0,   5,   5,   5,   5,   0,   0,
+       0,   0,   5,   5,   5,   5,   0,   0,
+       0,   0,   0,   0,   0,   0,   0,   0,
+       0,   0,   0,   0,   0,   0,   0,   0],
+];
+
+static PRINCESS_TERRAIN: [[i32; 64]; 2] = [
+    [-20, -10, -10,  -5,  -5, -10, -10, -20,
+     -10,   0,   0,   0,   0,   0,   0, -10,
+     -10,   0,   5,   5,   5,   5,   0, -10,
+      -5,   0,   5,   5,   5,   5,   0,  -5,
+       0,   0,   5,   5,   5,   5,   0,  -5,
+     -10,   5,   5,   5,   5,   5,   0, -10,
+     -10,   0,   5,   0,   0,   0,   0, -10,
+     -20, -10, -10,  -5,  -5, -10, -10, -20],
+    [-20, -10, -10,  -5,  -5, -10, -10, -20,
+     -10,   0,   5,   5,   5,   5,   0, -10,
+     -10,   5,  10,  10,  10,  10,   5, -10,
+      -5,   5,  10,  15,  15,  10,   5,  -5,
+      -5,   5,  10,  15,  15,  10,   5,  -5,
+     -10,   5,  10,  10,  10,  10,   5, -10,
+     -10,   0,   5,   5,   5,   5,   0, -10,
+     -20, -10, -10,  -5,  -5, -10, -10, -20],
+];
+
+// The figurehead hides behind his servants while there are officers about
+// to menace him, but once they're gone, he's needed in the fight.
+static FIGUREHEAD_TERRAIN: [[i32; 64]; 2] = [
+    [-30, -40, -40, -50, -50, -40, -40, -30,
+     -30, -40, -40, -50, -50, -40, -40, -30,
+     -30, -40, -40, -50, -50, -40, -40, -30,
+     -30, -40, -40, -50, -50, -40, -40, -30,
+     -20, -30, -30, -40, -40, -30, -30, -20,
+     -10, -20, -20, -20, -20, -20, -20, -10,
+      20,  20,   0,   0,   0,   0,  20,  20,
+      20,  30,  10,   0,   0,  10,  30,  20],
+    [-50, -40, -30, -20, -20, -30, -40, -50,
+     -30, -20, -10,   0,   0, -10, -20, -30,
+     -30, -10,  20,  30,  30,  20, -10, -30,
+     -30, -10,  30,  40,  40,  30, -10, -30,
+     -30, -10,  30,  40,  40,  30, -10, -30,
+     -30, -10,  20,  30,  30,  20, -10, -30,
+     -30, -30,   0,   0,   0,   0, -30, -30,
+     -50, -30, -30, -30, -30, -30, -30, -50],
+];
+
+fn terrain(job_description: JobDescription) -> &'static [[i32; 64]; 2] {
+    match job_description {
+        JobDescription::Servant => &SERVANT_TERRAIN,
+        JobDescription::Pony => &PONY_TERRAIN,
+        JobDescription::Scholar => &SCHOLAR_TERRAIN,
+        JobDescription::Cop => &COP_TERRAIN,
+        JobDescription::Princess => &PRINCESS_TERRAIN,
+        JobDescription::Figurehead => &FIGUREHEAD_TERRAIN,
+    }
+}
+
+/// What one team's standing is worth to them in the middlegame and in the
+/// endgame, respectively.
+pub fn standing(world: &WorldState, team: Team) -> (Score, Score) {
+    let mut middlegame = 0;
+    let mut endgame = 0;
+    for agent in &Agent::dramatis_personæ(team) {
+        let tables = terrain(agent.job_description);
+        let mut bits = world.agent_to_pinfield_ref(*agent).0;
+        while bits != 0 {
+            let pindex = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            // Blue sees the tables upside down, which is to say, the right
+            // way up
+            let seen = match team {
+                Team::Orange => pindex ^ 56,
+                Team::Blue => pindex,
+            };
+            middlegame += tables[0][seen];
+            endgame += tables[1][seen];
+        }
+    }
+    (Score(middlegame), Score(endgame))
+}
+
+/// What everyone's standing is worth (from Orange's perspective) in the
+/// middlegame and in the endgame, respectively.
+pub fn appraise(world: &WorldState) -> (Score, Score) {
+    let (orange_middlegame, orange_endgame) = standing(world, Team::Orange);
+    let (blue_middlegame, blue_endgame) = standing(world, Team::Blue);
+    (orange_middlegame - blue_middlegame, orange_endgame - blue_endgame)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{appraise, phase, taper, OPENING_PHASE};
+    use karma::Score;
+    use life::WorldState;
+
+    #[test]
+    fn concerning_phases() {
+        assert_eq!(OPENING_PHASE, phase(&WorldState::new()));
+        assert_eq!(0, phase(&WorldState::reconstruct(
+            "4k3/pppp4/8/8/8/8/4PPPP/4K3 w - -")));
+        assert_eq!(4, phase(&WorldState::reconstruct(
+            "4k3/8/8/8/8/8/8/3QK3 w - -")));
+
+        assert_eq!(Score(40), taper(Score(40), Score(-20), OPENING_PHASE));
+        assert_eq!(Score(-20), taper(Score(40), Score(-20), 0));
+        assert_eq!(Score(10), taper(Score(40), Score(-20), OPENING_PHASE / 2));
+    }
+
+    #[test]
+    fn concerning_terrain() {
+        assert_eq!((Score(0), Score(0)), appraise(&WorldState::new()));
+
+        // the figurehead belongs in his castle in the middlegame, but in the
+        // middle of things once the officers are gone
+        let sheltered = appraise(&WorldState::reconstruct(
+            "4k3/8/8/8/8/8/8/6K1 w - -"));
+        let emboldened = appraise(&WorldState::reconstruct(
+            "4k3/8/8/8/4K3/8/8/8 w - -"));
+        assert!(sheltered.0 > emboldened.0);
+        assert!(sheltered.1 < emboldened.1);
+
+        // and it's the same for Blue, the other way up
+        let blue_sheltered = appraise(&WorldState::reconstruct(
+            "6k1/8/8/8/8/8/8/4K3 w - -"));
+        let blue_emboldened = appraise(&WorldState::reconstruct(
+            "8/8/8/4k3/8/8/8/4K3 w - -"));
+        assert_eq!(sheltered, (-blue_sheltered.0, -blue_sheltered.1));
+        assert_eq!(emboldened, (-blue_emboldened.0, -blue_emboldened.1));
+    }
+}
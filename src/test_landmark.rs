@@ -1,8 +1,8 @@
 #[cfg(test)]
 mod tests {
     extern crate test;
-    use space::Pinfield;
-    use landmark::FILES;
+    use space::{Locale, Pinfield};
+    use landmark::{ADJACENT_FILES, FILES, HIGH_PASSED_SPANS, LOW_PASSED_SPANS};
 
     #[test]
     fn concerning_maps_and_territories() {
@@ -14,4 +14,27 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn concerning_neighbors_and_thoroughfares() {
+        assert_eq!(FILES[1], ADJACENT_FILES[0]);
+        assert_eq!(FILES[3] | FILES[5], ADJACENT_FILES[4]);
+
+        let e4 = Locale::from_algebraic("e4");
+        let span = Pinfield(HIGH_PASSED_SPANS[e4.pindex() as usize]);
+        assert_eq!(12, span.pincount());
+        assert!(span.query(Locale::from_algebraic("d5")));
+        assert!(span.query(Locale::from_algebraic("f8")));
+        assert!(!span.query(Locale::from_algebraic("e4")));
+        assert!(!span.query(Locale::from_algebraic("c5")));
+
+        let span = Pinfield(LOW_PASSED_SPANS[e4.pindex() as usize]);
+        assert_eq!(9, span.pincount());
+        assert!(span.query(Locale::from_algebraic("f1")));
+        assert!(!span.query(Locale::from_algebraic("e5")));
+
+        let a7 = Locale::from_algebraic("a7");
+        assert_eq!(2, Pinfield(HIGH_PASSED_SPANS[a7.pindex() as usize])
+                   .pincount());
+    }
 }
@@ -1,104 +1,375 @@
 use std::collections::HashMap;
 use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
 
-use time::Duration;
+use time::{Duration, get_time};
 
-use mind::{kickoff, iterative_deepening_kickoff, Variation};
-use life::WorldState;
+use mind::{leashed_iterative_deepening_kickoff, Curfew, Leash, SearchParams,
+           SearchStats, Variation};
+use hourglass::Hourglass;
+use karma::Score;
+use life::{Patch, WorldState};
+use scales::EvalParams;
 use space::Locale;
-use identity::Team;
+use identity::{Agent, Team};
 
 // Unlikely Command Integration dæmon
 
+/// the limits `go` can set on a search other than the clock
+fn explicit_curfew(options: &HashMap<&str, i64>) -> Option<Curfew> {
+    if let Some(&depth) = options.get("depth") {
+        Some(Curfew { depth: Some(depth.max(1) as u8), ..Curfew::default() })
+    } else if let Some(&millis) = options.get("movetime") {
+        Some(Curfew {
+            timeout: Some(Duration::milliseconds(millis)),
+            ..Curfew::default()
+        })
+    } else if let Some(&nodes) = options.get("nodes") {
+        Some(Curfew { nodes: Some(nodes.max(1) as usize), ..Curfew::default() })
+    } else if let Some(&movements) = options.get("mate") {
+        Some(Curfew { mate_in: Some(movements as u8), ..Curfew::default() })
+    } else {
+        None
+    }
+}
+
+fn clock_hourglass(options: &HashMap<&str, i64>, allegiance: Team)
+                   -> Option<Hourglass> {
+    let (time_key, increment_key) = match allegiance {
+        Team::Orange => ("wtime", "winc"),
+        Team::Blue => ("btime", "binc")
+    };
+    options.get(time_key).map(|&remaining_moments| {
+        let grace = options.get(increment_key).cloned().unwrap_or(0);
+        Hourglass::new(Duration::milliseconds(remaining_moments),
+                       Duration::milliseconds(grace),
+                       options.get("movestogo").map(|&m| m as u32))
+    })
+}
+
+fn report(depth: u8, score: Score, stats: &SearchStats,
+          thinking_time: Duration) {
+    let score_depiction = match score.mate_in() {
+        Some(movements) => format!("mate {}", movements),
+        None => format!("cp {}", score.0),
+    };
+    let millis = thinking_time.num_milliseconds().max(1) as u64;
+    println!("info depth {} seldepth {} score {} nodes {} nps {} time {}",
+             depth, stats.selective_depth, score_depiction,
+             stats.all_nodes(), stats.all_nodes() * 1000 / millis, millis);
+    println!("info string {}", stats);
+}
+
+fn movement_rune(patch: Patch, ascension: Option<Agent>) -> String {
+    format!("{}{}{}",
+            patch.whence.to_algebraic(),
+            patch.whither.to_algebraic(),
+            ascension.map(|a| a.to_preservation_rune().to_ascii_lowercase()
+                                .to_string())
+                     .unwrap_or_default())
+}
+
+/// Make the movement that the driver depicts as (e.g.) "e2e4" or "e7e8q".
+fn apply_movement_rune(world: WorldState, rune: &str) -> WorldState {
+    let whence = Locale::from_algebraic(&rune[0..2]);
+    let whither = Locale::from_algebraic(&rune[2..4]);
+    let ascension = rune[4..].chars().next()
+        .map(|r| Agent::from(r).job_description);
+    world.lookahead().into_iter()
+        .find(|c| c.patch.whence == whence && c.patch.whither == whither &&
+              c.ascension.map(|a| a.job_description) == ascension)
+        .unwrap_or_else(|| {
+            moral_panic!(format!("couldn't find the movement {:?}", rune))
+        })
+        .tree
+}
+
+/// the name and value of a `setoption` command (either of which might
+/// contain spaces)
+fn option_setting<'a, I>(tokens: I) -> (String, String)
+        where I: Iterator<Item=&'a str> {
+    let mut name = Vec::new();
+    let mut value = Vec::new();
+    let mut valuing = false;
+    for token in tokens {
+        match token {
+            "name" if !valuing && name.is_empty() => {}
+            "value" if !valuing => { valuing = true; }
+            _ if valuing => value.push(token),
+            _ => name.push(token),
+        }
+    }
+    (name.join(" "), value.join(" "))
+}
+
+fn reconstruct_position<'a, I>(mut tokens: I) -> WorldState
+        where I: Iterator<Item=&'a str> {
+    let world = match tokens.next() {
+        Some("startpos") => WorldState::new(),
+        Some("fen") => {
+            let runes = tokens.by_ref()
+                .take_while(|&t| t != "moves")
+                .collect::<Vec<_>>();
+            WorldState::reconstruct(&runes.join(" "))
+        }
+        other => {
+            moral_panic!(format!("expected `startpos` or `fen`, got {:?}", other))
+        }
+    };
+    tokens.filter(|&t| t != "moves").fold(world, apply_movement_rune)
+}
+
+
+/// A search running in the background, so that we can still hear about
+/// `stop` and `ponderhit` while it thinks.
+struct Rumination {
+    leash: Arc<Leash>,
+    /// While pondering (or analyzing "infinitely"), we mustn't announce our
+    /// best movement until we're told to, even if we've run out of things
+    /// to think about.
+    restraint: Arc<AtomicBool>,
+    /// the clock to go by if the opposition does what we expected
+    hourglass: Option<Hourglass>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl Rumination {
+    fn begin(world: WorldState, curfew: Curfew, leash: Leash,
+             params: SearchParams, restrained: bool,
+             hourglass: Option<Hourglass>) -> Self {
+        let leash = Arc::new(leash);
+        let restraint = Arc::new(AtomicBool::new(restrained));
+        let travel_leash = leash.clone();
+        let travel_restraint = restraint.clone();
+        let thread = thread::spawn(move || {
+            let start_thinking = get_time();
+            let (forecasts, depth, stats) =
+                leashed_iterative_deepening_kickoff::<Variation>(
+                    &world, curfew, travel_leash.clone(),
+                    params, false, 2.0);
+            while travel_restraint.load(Ordering::SeqCst) &&
+                !travel_leash.yanked() {
+                thread::sleep(::std::time::Duration::from_millis(2));
+            }
+            let (determination, score, variation) = match forecasts.first() {
+                Some(forecast) => forecast,
+                None => {
+                    println!("bestmove 0000");
+                    return;
+                }
+            };
+            report(depth, *score, &stats, get_time() - start_thinking);
+            // the reply we expect, which we'll think about on the
+            // opposition's time if the driver lets us
+            let anticipation = variation.get(1).and_then(|&expected| {
+                determination.tree.lookahead().into_iter()
+                    .find(|c| c.patch == expected)
+            });
+            match anticipation {
+                Some(reply) => {
+                    println!("bestmove {} ponder {}",
+                             movement_rune(determination.patch,
+                                           determination.ascension),
+                             movement_rune(reply.patch, reply.ascension));
+                }
+                None => {
+                    println!("bestmove {}",
+                             movement_rune(determination.patch,
+                                           determination.ascension));
+                }
+            }
+        });
+        Rumination { leash, restraint, hourglass, thread }
+    }
+
+    /// The opposition made the movement we were pondering: keep thinking,
+    /// but now on our own time.
+    fn ponderhit(&self) {
+        match self.hourglass {
+            Some(hourglass) => self.leash.impose(hourglass),
+            None => self.leash.yank(),
+        }
+        self.restraint.store(false, Ordering::SeqCst);
+    }
+
+    fn stop(self) {
+        self.leash.yank();
+        self.thread.join().expect("search thread panicked");
+    }
+
+    fn conclude(self) {
+        // (waiting for a search that's neither restrained nor infinite to
+        // end on its own)
+        if self.restraint.load(Ordering::SeqCst) {
+            self.leash.yank();
+        }
+        self.thread.join().expect("search thread panicked");
+    }
+}
+
+
 pub fn dæmon() {
     let mut input_buffer = String::new();
     let mut world = WorldState::new();
+    let mut rumination: Option<Rumination> = None;
+    let mut params = SearchParams::default();
 
     loop {
         input_buffer.clear();
         io::stdin().read_line(&mut input_buffer)
             .expect("expected to be able to read stdin");
 
-        match input_buffer.as_ref() {
-            "uci\n" => {
+        let mut tokens = input_buffer.split_whitespace();
+        let command = match tokens.next() {
+            Some(command) => command,
+            None => continue,
+        };
+        match command {
+            "uci" => {
                 println!("id name Leafline v. {}", env!("CARGO_PKG_VERSION"));
                 println!("id author Zack M. Davis and friends");
+                println!("option name Ponder type check default false");
+                println!("option name EvalParams type string default <empty>");
                 println!("uciok");
-                continue;
             }
-            "isready\n" => {
+            "isready" => {
                 println!("readyok");
-                continue;
             }
-            "ucinewgame\n" => {
-                world = WorldState::new();
-                continue;
+            "setoption" => {
+                // (we take pondering as it comes)
+                let (name, value) = option_setting(tokens);
+                if name == "EvalParams" {
+                    params.eval = if value.is_empty() || value == "<empty>" {
+                        EvalParams::default()
+                    } else {
+                        match EvalParams::load(&value) {
+                            Ok(eval) => eval,
+                            Err(error) => {
+                                println!("info string {}", error);
+                                params.eval
+                            }
+                        }
+                    };
+                }
             }
-            "quit\n" => {
-                break;
+            "ucinewgame" => {
+                if let Some(r) = rumination.take() {
+                    r.stop();
+                }
+                world = WorldState::new();
             }
-            _ => { /* hopefully, 'position', or 'go' */ }
-        }
-
-        let mut tokens = input_buffer.split_whitespace();
-        let command = tokens.next().expect("expected a command");
-        match command {
             "position" => {
-                // XXX HACK: let's assume our internal WorldState is correct
-                // and just look at the last-move diff
-                let diff = tokens.last()
-                    .expect("expected a discernable last move");
-                // XXX: we're going to need better movement-parsing than this
-                // to play a full game (consider secret service)
-                let whence = Locale::from_algebraic(&diff[0..2]);
-                let whither = Locale::from_algebraic(&diff[2..4]);
-                let premonitions = world.lookahead();
-                for premonition in &premonitions {
-                    if premonition.patch.whence == whence &&
-                        premonition.patch.whither == whither {
-                            world = premonition.tree;
-                    }
+                if let Some(r) = rumination.take() {
+                    r.conclude();
                 }
+                world = reconstruct_position(tokens);
             },
             "go" => {
+                if let Some(r) = rumination.take() {
+                    r.conclude();
+                }
                 let mut options = HashMap::new();
+                let mut pondering = false;
+                let mut infinitely = false;
                 while let Some(key) = tokens.next() {
+                    match key {
+                        "ponder" => { pondering = true; continue; }
+                        "infinite" => { infinitely = true; continue; }
+                        _ => {}
+                    }
+                    // (signed, because the clock can run negative if a
+                    // driver is generous about flag-falls)
                     let value = tokens.next()
-                        .expect("expected option value").parse::<usize>()
+                        .expect("expected option value").parse::<i64>()
                         .expect("expected an integer literal");
                     options.insert(key, value);
                 }
 
-                let mut forecasts;
-                if let Some(depth) = options.get("depth") {
-                    forecasts = kickoff::<Variation>(&world, *depth as u8, None, false, 2.0);
+                let hourglass = clock_hourglass(&options, world.initiative);
+                let curfew = if pondering || infinitely {
+                    // no clock applies until `ponderhit` (or ever)
+                    Curfew {
+                        depth: explicit_curfew(&options).and_then(|c| c.depth),
+                        ..Curfew::default()
+                    }
                 } else {
-                    let allegiance = world.initiative;
-                    let (time_key, increment_key) = match allegiance {
-                        Team::Orange => ("wtime", "winc"),
-                        Team::Blue => ("btime", "binc")
-                    };
-                    let remaining_movements = options.get("movestogo")
-                        .expect("expected movestogo");
-                    let remaining_moments = options.get(time_key).expect(time_key);
-                    let zero = 0; // XXX really? really??
-                    let grace = options.get(increment_key).unwrap_or(&zero);
-                    let deadline = ((remaining_moments /
-                                     remaining_movements) + grace) / 1000;
-                    let (beforecasts, depth) = iterative_deepening_kickoff::<Variation>(
-                        &world, Duration::seconds(deadline as i64), false, 2.0);
-                    forecasts = beforecasts;
-                    let centis = (forecasts[0].1 * 100.) as isize;
-                    println!("info depth {} score cp {}", depth, centis);
+                    explicit_curfew(&options).unwrap_or(Curfew {
+                        hourglass,
+                        ..Curfew::default()
+                    })
+                };
+                let leash = if pondering || infinitely {
+                    Leash::default()
+                } else {
+                    Leash::from_curfew(&curfew)
+                };
+                rumination = Some(Rumination::begin(
+                    world, curfew, leash, params, pondering || infinitely,
+                    hourglass));
+            }
+            "ponderhit" => {
+                if let Some(ref r) = rumination {
+                    r.ponderhit();
+                }
+            }
+            "stop" => {
+                if let Some(r) = rumination.take() {
+                    r.stop();
                 }
-                let movement = forecasts.swap_remove(0).0;
-                println!("bestmove {}{}",
-                         movement.patch.whence.to_algebraic(),
-                         movement.patch.whither.to_algebraic());
-                world = movement.tree;
+            }
+            "quit" => {
+                if let Some(r) = rumination.take() {
+                    r.stop();
+                }
+                break;
             }
             s => { moral_panic!(format!("got unrecognized UCI command {:?}", s)) }
         }
 
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::{movement_rune, option_setting, reconstruct_position};
+    use identity::{Agent, JobDescription, Team};
+    use life::WorldState;
+
+    #[test]
+    fn concerning_positions() {
+        let world = reconstruct_position(
+            "startpos moves e2e4 e7e5 g1f3".split_whitespace());
+        assert_eq!(
+            WorldState::reconstruct(
+                "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq -"),
+            world);
+
+        let world = reconstruct_position(
+            "fen 8/4P1k1/8/8/8/8/8/4K3 w - - 0 1 moves e7e8n"
+                .split_whitespace());
+        assert_eq!(1, world.orange_ponies.pincount());
+        assert_eq!(0, world.orange_servants.pincount());
+    }
+
+    #[test]
+    fn concerning_movement_runes() {
+        let world = WorldState::reconstruct("8/4P1k1/8/8/8/8/8/4K3 w - -");
+        let ascension = world.lookahead().into_iter()
+            .find(|c| c.ascension ==
+                  Some(Agent::new(Team::Orange, JobDescription::Princess)))
+            .unwrap();
+        assert_eq!("e7e8q", movement_rune(ascension.patch, ascension.ascension));
+    }
+
+    #[test]
+    fn concerning_option_settings() {
+        assert_eq!(("EvalParams".to_owned(), "/tmp/my params.txt".to_owned()),
+                   option_setting("name EvalParams value /tmp/my params.txt"
+                                  .split_whitespace()));
+        assert_eq!(("Clear Hash".to_owned(), "".to_owned()),
+                   option_setting("name Clear Hash".split_whitespace()));
+    }
+}